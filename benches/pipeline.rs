@@ -0,0 +1,111 @@
+//! Benchmark do pipeline de páginas: compara 1 worker com todos os núcleos
+//!
+//! Uso: `cargo bench --bench pipeline`
+//!
+//! Por padrão gera um PDF de teste com `PDF2KEY_BENCH_PAGES` páginas (padrão: 60);
+//! `PDF2KEY_BENCH_PDF` permite usar um arquivo próprio. Requer a biblioteca PDFium.
+
+use pdf2key::pdf_processor::PdfProcessor;
+use pdf2key::{pipeline, ConversionRequest, OutputFormat};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+fn main() {
+    if PdfProcessor::new().is_err() {
+        eprintln!("[bench] PDFium não encontrado; benchmark ignorado");
+        return;
+    }
+
+    let dir = tempfile::tempdir().expect("diretório temporário");
+    let pdf_path = match std::env::var_os("PDF2KEY_BENCH_PDF") {
+        Some(path) => path.into(),
+        None => {
+            let pages = std::env::var("PDF2KEY_BENCH_PAGES")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(60);
+            let path = dir.path().join("fixture.pdf");
+            write_fixture_pdf(&path, pages);
+            path
+        }
+    };
+
+    let output = dir.path().join("bench.pptx");
+    let jobs_all = pipeline::default_jobs();
+
+    let sequential = run(&pdf_path, &output, 1);
+    let parallel = run(&pdf_path, &output, jobs_all);
+
+    eprintln!("[bench] 1 worker:   {:>8.2?}", sequential);
+    eprintln!("[bench] {} workers: {:>8.2?}", jobs_all, parallel);
+    eprintln!(
+        "[bench] speedup:    {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+fn run(pdf_path: &Path, output: &Path, jobs: usize) -> Duration {
+    let request = ConversionRequest::new(pdf_path, output)
+        .with_format(OutputFormat::Pptx)
+        .with_jobs(jobs);
+
+    let start = Instant::now();
+    pdf2key::convert(&request, &()).expect("conversão do benchmark");
+    start.elapsed()
+}
+
+/// Gera um PDF A4 com `pages` páginas cheias de formas coloridas (caro de codificar)
+fn write_fixture_pdf(path: &Path, pages: usize) {
+    let mut objects: Vec<String> = Vec::new();
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+
+    let kids: Vec<String> = (0..pages).map(|i| format!("{} 0 R", 3 + i * 2)).collect();
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages));
+
+    for page in 0..pages {
+        let mut content = String::new();
+        for row in 0..40 {
+            for col in 0..28 {
+                let (r, g, b) = (
+                    ((row * 7 + page * 13) % 100) as f32 / 100.0,
+                    ((col * 11 + page * 3) % 100) as f32 / 100.0,
+                    ((row * col + page) % 100) as f32 / 100.0,
+                );
+                let _ = writeln!(
+                    content,
+                    "{r:.2} {g:.2} {b:.2} rg {x} {y} 18 18 re f",
+                    x = 20 + col * 20,
+                    y = 20 + row * 20
+                );
+            }
+        }
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] /Contents {} 0 R >>",
+            4 + page * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+
+    std::fs::write(path, pdf).expect("gravar PDF de teste");
+}
@@ -0,0 +1,596 @@
+//! Interface de linha de comando (sem janela)
+//! Ex: `pdf2key convert in.pdf -o out.key --size 1080p --format pptx`
+
+use pdf2key::keynote::KeynoteBackend;
+use pdf2key::images;
+use pdf2key::layout;
+use pdf2key::{
+    pipeline, BeamerNotes, ConversionRequest, FitMode, Handout, ImageEncoding, NotesSource, OutputFormat, OverlayMode,
+    PageOrder, PageSelection, Password, Pdf2KeyError, PdfInput, ProgressEvent, Resolution, SlideLayout, Trim, TrimSource,
+};
+use std::path::{Path, PathBuf};
+
+/// Códigos de saída do processo
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// Falha inesperada (ex: diretório temporário)
+    pub const FAILURE: i32 = 1;
+    /// Argumentos inválidos
+    pub const USAGE: i32 = 2;
+    /// O PDF não pôde ser aberto
+    pub const PDF_OPEN: i32 = 3;
+    /// Biblioteca PDFium não encontrada
+    pub const PDFIUM_MISSING: i32 = 4;
+    /// Falha ao renderizar ou gravar as páginas
+    pub const RENDER_FAILED: i32 = 5;
+    /// Falha no backend de saída (Keynote, PPTX, ODP)
+    pub const BACKEND_FAILED: i32 = 6;
+    /// O PDF é protegido por senha e ela não foi informada ou está errada
+    pub const PASSWORD_REQUIRED: i32 = 7;
+    /// Conversão cancelada
+    pub const CANCELLED: i32 = 130;
+}
+
+/// Variável de ambiente com a senha do PDF (preferível a --password, que fica visível em `ps`)
+const PASSWORD_ENV: &str = "PDF2KEY_PASSWORD";
+
+const USAGE: &str = "\
+Uso:
+  pdf2key                               Abre a interface gráfica
+  pdf2key convert <entrada.pdf>... [opções]
+                                        Vários PDFs viram uma apresentação só, na ordem dada;
+                                        'arquivo.pdf[1-3]' escolhe as páginas de um deles
+  pdf2key images <entrada.pdf> [-o <pasta>] [-p <seleção>] [--password <senha>]
+                                        Exporta as imagens embutidas na resolução original
+
+Opções:
+  -o, --output <arquivo>     Arquivo de saída (padrão: a primeira entrada com a extensão do formato)
+  -f, --format <formato>     key | pptx | odp (padrão: pela extensão de --output, ou key)
+      --size <tamanho>       Tamanho máximo das imagens, calculado para cada página: 1920x1080 | 1080p |
+                             4k | keynote | keynote2x (padrão: keynote2x, 3840x2160)
+      --dpi <n>              Resolução fixa em vez de --size, qualquer que seja o tamanho da página
+      --fit <modo>           Encaixe das páginas: match (slide na proporção da primeira página) | letterbox
+                             (página inteira, com faixas) | crop (cobre o slide, corta o excesso) |
+                             stretch (deforma) (padrão: match)
+      --slide-size <tamanho> Tamanho do slide em pontos para letterbox, crop e stretch: 16:9 | 4:3 |
+                             <largura>x<altura> (padrão: 16:9, 1920x1080)
+      --background <cor>     Cor das faixas, ex: #000000 (padrão: #ffffff)
+      --trim <origem>        Corta as margens: pixels (conteúdo na imagem) | objects (caixas dos objetos
+                             do PDF) | cropbox | trimbox | artbox (caixas definidas no PDF)
+      --trim-padding <pt>    Folga em volta do conteúdo, em pontos (padrão: 12; 0 com as caixas do PDF)
+      --trim-uniform         Mesmo corte em todas as páginas, para o conteúdo não pular entre slides
+      --image-format <fmt>   Imagens dos slides: png | webp[:qualidade] | jpeg[:qualidade] | auto[:qualidade]
+                             (auto: JPEG nas páginas com fotos, PNG no resto; qualidade padrão: 85)
+      --max-size <tamanho>   Limite para o tamanho da apresentação, ex: 25M ou 500K; baixa a qualidade
+                             JPEG de todas as páginas até caber
+  -p, --pages <seleção>      Páginas a converter, ex: 1-5,8,12- | odd | even | 'last 3' (padrão: todas);
+                             com vários PDFs, vale para os que não têm seleção própria
+      --file-sections        Uma seção por PDF de entrada, com o nome do arquivo (slide divisório no
+                             Keynote e no Impress); substitui as seções do sumário
+      --handout <grade>      Divide apostilas (vários slides por página) em slides: auto (detecta a grade
+                             em cada página) | <colunas>x<linhas>, ex: 2x3
+      --handout-order <ord>  Ordem dos slides na página: rows (linha a linha) | columns (coluna a coluna)
+                             (padrão: rows)
+      --handout-keep-frames  Usa as células inteiras, sem cortar o espaço em branco e as molduras
+      --notes <origem>       Notas do apresentador: none | text (texto da página) (padrão: none)
+      --notes-file <arquivo> Notas de um arquivo de texto, uma página por bloco separado por ---
+      --beamer-notes <lado>  Páginas do Beamer com notas ao lado: right | left | auto; usa só o slide
+                             e leva o texto da outra metade às notas (implica --notes text)
+      --overlays <modo>      Overlays do Beamer (\\pause, \\only): off | last (só a última página de cada
+                             frame) | builds (um slide por frame, cada passo aparece com um clique)
+      --no-outline           Não usa o sumário e os rótulos do PDF como nomes e seções dos slides
+      --no-links             Não cria áreas clicáveis para os links do PDF (os links só existem no
+                             PowerPoint e no Impress; o Keynote não os recebe)
+      --editable             Textos do PDF viram caixas de texto editáveis e imagens viram objetos
+                             (erro com --keynote native, que não grava objetos)
+      --native-images        Fotos do PDF na resolução original sobre o fundo, em vez de renderizadas
+                             com a página (erro com --keynote native)
+      --vector               Slides também em SVG vetorial (a imagem fica como alternativa); páginas com
+                             sombreamentos, transparências etc. ficam só na imagem e são listadas
+      --password <senha>     Senha do PDF protegido (ou use a variável PDF2KEY_PASSWORD); com vários PDFs,
+                             vale para os que não têm senha própria
+      --file-password <n>:<senha>
+                             Senha só do n-ésimo PDF de entrada (1-based), ex: 2:segredo
+  -j, --jobs <n>             Páginas codificadas em paralelo (padrão: número de núcleos)
+      --keynote <backend>    native | applescript (padrão: applescript no macOS, native nos demais)
+  -v, --verbose              Mostra os detalhes de cada etapa
+  -h, --help                 Mostra esta ajuda
+
+Códigos de saída:
+  0 sucesso, 1 falha inesperada, 2 argumentos ou seleção de páginas inválidos, 3 PDF não pôde ser aberto,
+  4 PDFium não encontrado, 5 falha na renderização, 6 falha no backend de saída,
+  7 PDF protegido (senha ausente ou incorreta), 130 cancelada";
+
+/// Mensagens de diagnóstico da biblioteca (`log`) no stderr
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("pdf2key: {}: {}", record.level().as_str().to_lowercase(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn init_logger(level: log::LevelFilter) {
+    static LOGGER: StderrLogger = StderrLogger;
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Opções do comando `convert`
+struct ConvertArgs {
+    inputs: Vec<PdfInput>,
+    output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    resolution: Resolution,
+    layout: SlideLayout,
+    trim: Option<Trim>,
+    pages: PageSelection,
+    file_sections: bool,
+    handout: Option<Handout>,
+    notes: NotesSource,
+    beamer_notes: BeamerNotes,
+    overlays: OverlayMode,
+    outline: bool,
+    links: bool,
+    editable: bool,
+    native_images: bool,
+    vector: bool,
+    encoding: ImageEncoding,
+    max_size: Option<u64>,
+    password: Option<Password>,
+    jobs: usize,
+    keynote_backend: KeynoteBackend,
+    verbose: bool,
+}
+
+/// Opções do comando `images`
+struct ImagesArgs {
+    input: PathBuf,
+    /// Pasta de saída (padrão: `<entrada>_imagens` ao lado do PDF)
+    output: Option<PathBuf>,
+    pages: PageSelection,
+    password: Option<Password>,
+}
+
+/// Executa a linha de comando e retorna o código de saída
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("-h") | Some("--help") | Some("help") => {
+            println!("{}", USAGE);
+            exit_code::SUCCESS
+        }
+        Some("convert") => match parse_convert(&args[1..]) {
+            Ok(convert_args) => convert(convert_args),
+            Err(message) => {
+                eprintln!("pdf2key: {}\n\n{}", message, USAGE);
+                exit_code::USAGE
+            }
+        },
+        Some("images") => match parse_images(&args[1..]) {
+            Ok(images_args) => dump_images(images_args),
+            Err(message) => {
+                eprintln!("pdf2key: {}\n\n{}", message, USAGE);
+                exit_code::USAGE
+            }
+        },
+        Some(other) => {
+            eprintln!("pdf2key: comando desconhecido '{}'\n\n{}", other, USAGE);
+            exit_code::USAGE
+        }
+        None => {
+            eprintln!("{}", USAGE);
+            exit_code::USAGE
+        }
+    }
+}
+
+fn parse_convert(args: &[String]) -> Result<ConvertArgs, String> {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut format = None;
+    let mut resolution = Resolution::default();
+    let mut layout = SlideLayout::default();
+    let mut trim_source = None;
+    let mut trim_padding = None;
+    let mut trim_uniform = false;
+    let mut pages = PageSelection::all();
+    let mut file_sections = false;
+    let mut handout = None;
+    let mut handout_order = None;
+    let mut handout_frames = false;
+    let mut notes = None;
+    let mut beamer_notes = BeamerNotes::Off;
+    let mut overlays = OverlayMode::Off;
+    let mut outline = true;
+    let mut links = true;
+    let mut editable = false;
+    let mut native_images = false;
+    let mut vector = false;
+    let mut encoding = ImageEncoding::default();
+    let mut max_size = None;
+    let mut password = std::env::var(PASSWORD_ENV).ok().filter(|p| !p.is_empty()).map(Password::new);
+    let mut file_passwords = Vec::new();
+    let mut jobs = pipeline::default_jobs();
+    let mut keynote_backend = KeynoteBackend::default();
+    let mut verbose = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("faltou o valor de {}", name))
+        };
+
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(value(arg)?)),
+            "-f" | "--format" => {
+                let name = value(arg)?;
+                format = Some(OutputFormat::from_name(&name).ok_or(format!("formato inválido '{}'", name))?);
+            }
+            "--dpi" => {
+                let text = value(arg)?;
+                let dpi = text.parse().ok().filter(|d| *d > 0).ok_or(format!("DPI inválido '{}'", text))?;
+                resolution = Resolution::Dpi(dpi);
+            }
+            "--size" => {
+                let text = value(arg)?;
+                resolution = Resolution::from_name(&text).ok_or(format!("tamanho de imagem inválido '{}'", text))?;
+            }
+            "--fit" => {
+                let text = value(arg)?;
+                layout.fit = FitMode::from_name(&text).ok_or(format!("modo de encaixe inválido '{}'", text))?;
+            }
+            "--slide-size" => {
+                let text = value(arg)?;
+                let (width, height) = layout::parse_slide_size(&text).ok_or(format!("tamanho de slide inválido '{}'", text))?;
+                layout = layout.with_size(width, height);
+            }
+            "--background" => {
+                let text = value(arg)?;
+                layout.background = layout::parse_color(&text).ok_or(format!("cor inválida '{}'", text))?;
+            }
+            "--trim" => {
+                let text = value(arg)?;
+                trim_source = Some(TrimSource::from_name(&text).ok_or(format!("origem do corte inválida '{}'", text))?);
+            }
+            "--trim-padding" => {
+                let text = value(arg)?;
+                let padding = text.parse().ok().filter(|p: &f32| *p >= 0.0).ok_or(format!("folga inválida '{}'", text))?;
+                trim_padding = Some(padding);
+            }
+            "--trim-uniform" => trim_uniform = true,
+            "-p" | "--pages" => {
+                let expression = value(arg)?;
+                pages = PageSelection::parse(&expression)
+                    .map_err(|e| format!("seleção de páginas inválida: {}", e))?;
+            }
+            "--file-sections" => file_sections = true,
+            "--handout" => {
+                let text = value(arg)?;
+                handout = Some(Handout::from_name(&text).ok_or(format!("grade de apostila inválida '{}'", text))?);
+            }
+            "--handout-order" => {
+                let text = value(arg)?;
+                handout_order = Some(PageOrder::from_name(&text).ok_or(format!("ordem de apostila inválida '{}'", text))?);
+            }
+            "--handout-keep-frames" => handout_frames = true,
+            "--notes" => {
+                notes = Some(match value(arg)?.as_str() {
+                    "none" => NotesSource::None,
+                    "text" => NotesSource::PageText,
+                    other => return Err(format!("origem de notas inválida '{}'", other)),
+                });
+            }
+            "--notes-file" => notes = Some(NotesSource::Sidecar(PathBuf::from(value(arg)?))),
+            "--beamer-notes" => {
+                beamer_notes = match value(arg)?.as_str() {
+                    "right" => BeamerNotes::Right,
+                    "left" => BeamerNotes::Left,
+                    "auto" => BeamerNotes::Auto,
+                    other => return Err(format!("lado das notas do Beamer inválido '{}'", other)),
+                };
+            }
+            "--overlays" => {
+                overlays = match value(arg)?.as_str() {
+                    "off" => OverlayMode::Off,
+                    "last" => OverlayMode::LastFrame,
+                    "builds" => OverlayMode::Builds,
+                    other => return Err(format!("modo de overlays inválido '{}'", other)),
+                };
+            }
+            "--no-outline" => outline = false,
+            "--no-links" => links = false,
+            "--editable" => editable = true,
+            "--native-images" => native_images = true,
+            "--vector" => vector = true,
+            "--image-format" => {
+                let text = value(arg)?;
+                encoding = ImageEncoding::from_name(&text).ok_or(format!("formato de imagem inválido '{}'", text))?;
+            }
+            "--max-size" => {
+                let text = value(arg)?;
+                max_size = Some(parse_size(&text).ok_or(format!("tamanho inválido '{}'", text))?);
+            }
+            "--password" => password = Some(Password::new(value(arg)?)),
+            "--file-password" => {
+                let text = value(arg)?;
+                // Sem repetir a senha na mensagem de erro
+                let (index, file_password) = text
+                    .split_once(':')
+                    .and_then(|(index, file_password)| Some((index.trim().parse::<usize>().ok().filter(|i| *i > 0)?, file_password)))
+                    .ok_or("--file-password espera <n>:<senha>, com n a partir de 1")?;
+                file_passwords.push((index, Password::new(file_password)));
+            }
+            "-j" | "--jobs" => {
+                let text = value(arg)?;
+                jobs = text.parse().ok().filter(|j| *j > 0).ok_or(format!("número de jobs inválido '{}'", text))?;
+            }
+            "--keynote" => {
+                keynote_backend = match value(arg)?.as_str() {
+                    "native" => KeynoteBackend::Native,
+                    "applescript" => KeynoteBackend::AppleScript,
+                    other => return Err(format!("backend do Keynote inválido '{}'", other)),
+                };
+            }
+            "-v" | "--verbose" => verbose = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("opção desconhecida '{}'", flag));
+            }
+            path => inputs.push(parse_input(path)?),
+        }
+    }
+
+    // --trim-padding e --trim-uniform sem --trim cortam pelos pixels
+    let trim = match (trim_source, trim_padding, trim_uniform) {
+        (None, None, false) => None,
+        (source, padding, uniform) => {
+            let trim = Trim::new(source.unwrap_or_default()).with_uniform(uniform);
+            Some(match padding {
+                Some(padding) => trim.with_padding(padding),
+                None => trim,
+            })
+        }
+    };
+
+    // --handout-order e --handout-keep-frames sem --handout detectam a grade
+    let handout = match (handout, handout_order, handout_frames) {
+        (None, None, false) => None,
+        (handout, order, frames) => Some(
+            handout
+                .unwrap_or_default()
+                .with_order(order.unwrap_or_default())
+                .with_borders(!frames),
+        ),
+    };
+
+    if inputs.is_empty() {
+        return Err("informe o arquivo PDF de entrada".to_string());
+    }
+    let count = inputs.len();
+    for (index, file_password) in file_passwords {
+        let input: &mut PdfInput = inputs
+            .get_mut(index - 1)
+            .ok_or(format!("--file-password {}: há só {} PDF(s) de entrada", index, count))?;
+        input.password = Some(file_password);
+    }
+
+    Ok(ConvertArgs {
+        inputs,
+        output,
+        format,
+        resolution,
+        layout,
+        trim,
+        pages,
+        file_sections,
+        handout,
+        // Dividir páginas do Beamer só faz sentido levando o texto às notas
+        notes: notes.unwrap_or(if beamer_notes == BeamerNotes::Off {
+            NotesSource::None
+        } else {
+            NotesSource::PageText
+        }),
+        beamer_notes,
+        overlays,
+        outline,
+        links,
+        editable,
+        native_images,
+        vector,
+        encoding,
+        max_size,
+        password,
+        jobs,
+        keynote_backend,
+        verbose,
+    })
+}
+
+/// PDF de entrada, com a seleção de páginas opcional entre colchetes: `apendice.pdf[1-3]`
+///
+/// Um arquivo que existe com esse nome é usado como está.
+fn parse_input(arg: &str) -> Result<PdfInput, String> {
+    let selection = arg
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .filter(|_| !Path::new(arg).exists());
+    match selection {
+        Some((path, expression)) => {
+            let pages = PageSelection::parse(expression)
+                .map_err(|e| format!("seleção de páginas inválida em '{}': {}", arg, e))?;
+            Ok(PdfInput::new(path).with_pages(pages))
+        }
+        None => Ok(PdfInput::new(arg)),
+    }
+}
+
+/// Tamanho em bytes, com sufixo opcional K, M ou G (potências de 1000, como nos limites de e-mail)
+fn parse_size(text: &str) -> Option<u64> {
+    let upper = text.trim().to_ascii_uppercase();
+    let digits = upper.trim_end_matches('B');
+    let (number, multiplier) = match digits.char_indices().last()? {
+        (i, 'K') => (&digits[..i], 1_000.0),
+        (i, 'M') => (&digits[..i], 1_000_000.0),
+        (i, 'G') => (&digits[..i], 1_000_000_000.0),
+        _ => (digits, 1.0),
+    };
+    let value: f64 = number.trim().replace(',', ".").parse().ok()?;
+    (value > 0.0).then_some((value * multiplier) as u64)
+}
+
+fn convert(args: ConvertArgs) -> i32 {
+    init_logger(if args.verbose { log::LevelFilter::Debug } else { log::LevelFilter::Warn });
+
+    // Formato: --format, senão a extensão da saída, senão Keynote
+    let format = args.format
+        .or_else(|| {
+            args.output.as_ref()
+                .and_then(|o| o.extension())
+                .and_then(|e| OutputFormat::from_name(&e.to_string_lossy()))
+        })
+        .unwrap_or_default();
+
+    let output = args.output.clone().unwrap_or_else(|| {
+        let mut output = args.inputs[0].path.clone();
+        output.set_extension(format.extension());
+        output
+    });
+
+    let report = |event: &ProgressEvent| match event {
+        // O fim é anunciado abaixo, junto com o caminho da saída
+        ProgressEvent::Finished => {}
+        ProgressEvent::Warning(_) => eprintln!("pdf2key: aviso: {}", event.message()),
+        _ => eprintln!("[{:>3.0}%] {}", event.fraction() * 100.0, event.message()),
+    };
+
+    let mut request = ConversionRequest::new(&args.inputs[0].path, &output)
+        .with_inputs(args.inputs)
+        .with_format(format)
+        .with_keynote_backend(args.keynote_backend)
+        .with_resolution(args.resolution)
+        .with_layout(args.layout)
+        .with_trim(args.trim)
+        .with_pages(args.pages)
+        .with_file_sections(args.file_sections)
+        .with_handout(args.handout)
+        .with_notes(args.notes)
+        .with_beamer_notes(args.beamer_notes)
+        .with_overlays(args.overlays)
+        .with_outline(args.outline)
+        .with_links(args.links)
+        .with_editable(args.editable)
+        .with_native_images(args.native_images)
+        .with_vector(args.vector)
+        .with_encoding(args.encoding)
+        .with_max_size(args.max_size)
+        .with_jobs(args.jobs);
+    if let Some(password) = args.password {
+        request = request.with_password(password);
+    }
+
+    match pdf2key::convert(&request, &report) {
+        Ok(()) => {
+            eprintln!("[100%] Concluído: {}", output.display());
+            exit_code::SUCCESS
+        }
+        Err(e) => error_exit_code(&e),
+    }
+}
+
+fn parse_images(args: &[String]) -> Result<ImagesArgs, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut pages = PageSelection::all();
+    let mut password = std::env::var(PASSWORD_ENV).ok().filter(|p| !p.is_empty()).map(Password::new);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("faltou o valor de {}", name))
+        };
+
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(value(arg)?)),
+            "-p" | "--pages" => {
+                let expression = value(arg)?;
+                pages = PageSelection::parse(&expression)
+                    .map_err(|e| format!("seleção de páginas inválida: {}", e))?;
+            }
+            "--password" => password = Some(Password::new(value(arg)?)),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("opção desconhecida '{}'", flag));
+            }
+            path if input.is_none() => input = Some(PathBuf::from(path)),
+            extra => return Err(format!("argumento inesperado '{}'", extra)),
+        }
+    }
+
+    Ok(ImagesArgs {
+        input: input.ok_or("informe o arquivo PDF de entrada")?,
+        output,
+        pages,
+        password,
+    })
+}
+
+fn dump_images(args: ImagesArgs) -> i32 {
+    let output = args.output.clone().unwrap_or_else(|| {
+        let stem = args.input.file_stem().unwrap_or_default().to_string_lossy();
+        args.input.with_file_name(format!("{}_imagens", stem))
+    });
+
+    match images::dump(&args.input, args.password.as_ref(), &args.pages, &output) {
+        Ok(dumped) => {
+            // Uma linha por imagem na saída padrão: arquivo, tamanho nativo e posição na página
+            // (em pontos); o stderr fica com o progresso e os avisos
+            for image in &dumped {
+                let at = image.placement;
+                println!(
+                    "página {}: {} {}x{} px em ({}, {}) {}x{} pt",
+                    image.page,
+                    image.path.display(),
+                    image.width,
+                    image.height,
+                    at.x,
+                    at.y,
+                    at.width,
+                    at.height
+                );
+            }
+            eprintln!("[100%] {} imagens em {}", dumped.len(), output.display());
+            exit_code::SUCCESS
+        }
+        Err(e) => error_exit_code(&e),
+    }
+}
+
+/// Mostra o erro e escolhe o código de saída correspondente
+fn error_exit_code(e: &Pdf2KeyError) -> i32 {
+    eprintln!("pdf2key: erro: {}", e);
+    match e {
+        Pdf2KeyError::PdfiumNotFound { .. } => exit_code::PDFIUM_MISSING,
+        Pdf2KeyError::PdfOpen { .. } => exit_code::PDF_OPEN,
+        Pdf2KeyError::PasswordRequired { .. } | Pdf2KeyError::IncorrectPassword { .. } => {
+            exit_code::PASSWORD_REQUIRED
+        }
+        Pdf2KeyError::InvalidPageSelection(_) | Pdf2KeyError::UnsupportedOption { .. } => exit_code::USAGE,
+        Pdf2KeyError::PageRender { .. } | Pdf2KeyError::PageText { .. } | Pdf2KeyError::ImageEncode { .. } => {
+            exit_code::RENDER_FAILED
+        }
+        Pdf2KeyError::BackendFailed { .. } => exit_code::BACKEND_FAILED,
+        Pdf2KeyError::Io { .. } => exit_code::FAILURE,
+        Pdf2KeyError::Cancelled => exit_code::CANCELLED,
+    }
+}
@@ -0,0 +1,892 @@
+//! Conversão de PDF em apresentação
+//! API pública usada pela interface gráfica, pela linha de comando e por outras ferramentas
+
+use crate::editable::{PageObject, PlacedImage, SlideObject};
+use crate::encoding::{self, ImageEncoding};
+use crate::error::Pdf2KeyError;
+use crate::handout::{self, Handout};
+use crate::keynote::{self, KeynoteBackend};
+use crate::layout::SlideLayout;
+use crate::links;
+use crate::notes::{self, NotesSource};
+use crate::outline;
+use crate::overlays::{self, OverlayMode, Thumbnail};
+use crate::page_selection::{PageSelection, PageSelectionError};
+use crate::pdf_processor::{BeamerNotes, Password, Resolution};
+use crate::slide::Slide;
+use crate::trim::{self, Trim};
+use crate::vector::VectorPage;
+use crate::{odp, pdf_processor, pipeline, pptx};
+use image::{ImageFormat, RgbaImage};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Formato da apresentação gerada
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Keynote,
+    Pptx,
+    Odp,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 3] = [OutputFormat::Keynote, OutputFormat::Pptx, OutputFormat::Odp];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Keynote => "key",
+            OutputFormat::Pptx => "pptx",
+            OutputFormat::Odp => "odp",
+        }
+    }
+
+    /// Interpreta o nome do formato ou a extensão do arquivo
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "key" | "keynote" => Some(OutputFormat::Keynote),
+            "pptx" | "powerpoint" => Some(OutputFormat::Pptx),
+            "odp" | "impress" => Some(OutputFormat::Odp),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputFormat::Keynote => "Keynote",
+            OutputFormat::Pptx => "PowerPoint",
+            OutputFormat::Odp => "Impress",
+        }
+    }
+}
+
+/// Evento de andamento emitido durante a conversão
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// PDF aberto; `total` páginas serão convertidas
+    Started { total: usize },
+    /// Página `page` (número no documento, 1-based) renderizada pelo PDFium
+    PageRendered { page: usize, done: usize, total: usize },
+    /// Página `page` codificada e gravada em disco
+    PageEncoded { page: usize, done: usize, total: usize },
+    /// Página `page` não pôde ser convertida em SVG e fica como imagem (`reason`, ex: "sombreamento")
+    RasterFallback { page: usize, reason: &'static str, done: usize, total: usize },
+    /// Parte do pedido não pôde ser atendida; a conversão segue sem ela
+    Warning(ConversionWarning),
+    /// Slide `done` de `total` gravado na apresentação
+    SlideBuilt { done: usize, total: usize },
+    /// Cancelamento pedido; a conversão para na próxima página
+    Cancelling,
+    /// Apresentação gerada
+    Finished,
+}
+
+impl ProgressEvent {
+    /// Fração aproximada do trabalho concluído (0.0 a 1.0)
+    ///
+    /// Renderização e codificação acontecem em paralelo, então eventos consecutivos
+    /// podem vir com frações fora de ordem; quem exibe deve manter o maior valor.
+    pub fn fraction(&self) -> f32 {
+        let ratio = |done: usize, total: usize| done as f32 / total.max(1) as f32;
+        match *self {
+            ProgressEvent::Started { .. } => 0.05,
+            ProgressEvent::PageRendered { done, total, .. } | ProgressEvent::RasterFallback { done, total, .. } => {
+                0.05 + 0.35 * ratio(done, total)
+            }
+            ProgressEvent::PageEncoded { done, total, .. } => 0.05 + 0.75 * ratio(done, total),
+            ProgressEvent::SlideBuilt { done, total } => 0.8 + 0.2 * ratio(done, total),
+            ProgressEvent::Warning(_) | ProgressEvent::Cancelling => 0.0,
+            ProgressEvent::Finished => 1.0,
+        }
+    }
+
+    /// Mensagem para exibir ao usuário
+    pub fn message(&self) -> String {
+        match *self {
+            ProgressEvent::Warning(ref warning) => warning.message(),
+            ProgressEvent::Started { total } => format!("Convertendo {} página(s)...", total),
+            ProgressEvent::PageRendered { page, done, total } => {
+                format!("Renderizando página {} ({} de {})...", page, done, total)
+            }
+            ProgressEvent::PageEncoded { page, done, total } => {
+                format!("Processando página {} ({} de {})...", page, done, total)
+            }
+            ProgressEvent::RasterFallback { page, reason, .. } => {
+                format!("Página {} mantida como imagem ({})", page, reason)
+            }
+            ProgressEvent::SlideBuilt { done, total } => format!("Criando slide {} de {}...", done, total),
+            ProgressEvent::Cancelling => "Cancelando...".to_string(),
+            ProgressEvent::Finished => "Concluído!".to_string(),
+        }
+    }
+}
+
+/// Aviso de uma conversão que seguiu sem parte do pedido
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionWarning {
+    /// O Keynote nativo (e o Keynote com builds, que passa por ele) não grava SVG: os slides ficam só com as imagens
+    VectorUnsupported,
+    /// Builds trocados pela última página de cada frame (`reason`, ex: "objetos extraídos")
+    BuildsUnsupported { reason: &'static str },
+    /// Apresentação com `size` bytes mesmo na qualidade mínima, acima de `limit`
+    SizeLimitExceeded { size: u64, limit: u64 },
+    /// O Keynote não grava links: `count` links do PDF ficaram de fora
+    LinksDropped { count: usize },
+}
+
+impl ConversionWarning {
+    pub fn message(&self) -> String {
+        match *self {
+            ConversionWarning::VectorUnsupported => {
+                "O pacote nativo do Keynote não suporta slides vetoriais; usando imagens das páginas".to_string()
+            }
+            ConversionWarning::BuildsUnsupported { reason } => {
+                format!("Builds não suportados ({}); usando a última página de cada frame", reason)
+            }
+            ConversionWarning::SizeLimitExceeded { size, limit } => format!(
+                "Apresentação com {:.1} MB mesmo na qualidade mínima (limite: {:.1} MB)",
+                size as f64 / 1e6,
+                limit as f64 / 1e6
+            ),
+            ConversionWarning::LinksDropped { count } => {
+                format!("{} links ignorados: o Keynote não suporta links", count)
+            }
+        }
+    }
+}
+
+/// Recebe o andamento da conversão
+///
+/// Implementado para qualquer `Fn(&ProgressEvent)` e para `()` (ignora o progresso).
+pub trait ProgressSink {
+    fn report(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent)> ProgressSink for F {
+    fn report(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+impl ProgressSink for () {
+    fn report(&self, _event: &ProgressEvent) {}
+}
+
+/// Permite cancelar uma conversão em andamento a partir de outra thread
+///
+/// Clones compartilham o mesmo estado. O cancelamento é verificado entre páginas.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pede o cancelamento da conversão
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Erro de cancelamento, se ele foi pedido
+    pub(crate) fn check(&self) -> Result<(), Pdf2KeyError> {
+        if self.is_cancelled() {
+            return Err(Pdf2KeyError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Um dos PDFs da apresentação
+#[derive(Debug, Clone)]
+pub struct PdfInput {
+    pub path: PathBuf,
+    /// Páginas deste PDF (padrão: as de `ConversionRequest::pages`)
+    pub pages: Option<PageSelection>,
+    /// Título da seção deste PDF com `ConversionRequest::file_sections` (padrão: o nome do arquivo)
+    pub title: Option<String>,
+    /// Senha deste PDF (padrão: `ConversionRequest::password`)
+    pub password: Option<Password>,
+}
+
+impl PdfInput {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), pages: None, title: None, password: None }
+    }
+
+    pub fn with_pages(mut self, pages: PageSelection) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_password(mut self, password: Password) -> Self {
+        self.password = Some(password);
+        self
+    }
+
+    /// Título da seção: o informado ou o nome do arquivo sem a extensão
+    pub fn section_title(&self) -> String {
+        self.title
+            .clone()
+            .unwrap_or_else(|| self.path.file_stem().unwrap_or_default().to_string_lossy().to_string())
+    }
+}
+
+/// Parâmetros de uma conversão
+#[derive(Debug, Clone)]
+pub struct ConversionRequest {
+    /// PDFs de entrada, na ordem dos slides
+    pub inputs: Vec<PdfInput>,
+    /// Senha dos PDFs protegidos que não têm a sua em `PdfInput::password`
+    pub password: Option<Password>,
+    pub output: PathBuf,
+    pub format: OutputFormat,
+    pub keynote_backend: KeynoteBackend,
+    /// Tamanho das imagens das páginas (padrão: o dobro do slide do Keynote, 3840×2160)
+    pub resolution: Resolution,
+    /// Tamanho dos slides, encaixe das páginas e cor de fundo (padrão: slide na proporção da primeira página)
+    pub layout: SlideLayout,
+    /// Corte das margens das páginas (padrão: nenhum)
+    pub trim: Option<Trim>,
+    /// Páginas a converter de cada PDF sem seleção própria (padrão: todas)
+    pub pages: PageSelection,
+    /// Abre uma seção com o título de cada PDF, com slide divisório no Keynote e no Impress
+    /// (padrão: não); as seções do sumário dos PDFs deixam de ser usadas
+    pub file_sections: bool,
+    /// Divisão de páginas de apostila em um slide por célula (padrão: nenhuma)
+    pub handout: Option<Handout>,
+    /// Origem das notas do apresentador (padrão: nenhuma)
+    pub notes: NotesSource,
+    /// Divisão de páginas do Beamer com notas ao lado do slide
+    pub beamer_notes: BeamerNotes,
+    /// Tratamento das páginas de overlay do Beamer (padrão: uma página por slide)
+    pub overlays: OverlayMode,
+    /// Usa o sumário e os rótulos das páginas como nomes e seções dos slides (padrão: sim)
+    pub outline: bool,
+    /// Mantém os links do PDF como áreas clicáveis nos slides (padrão: sim; só PPTX e ODP)
+    pub links: bool,
+    /// Recria textos e imagens do PDF como objetos editáveis sobre o fundo (padrão: não)
+    pub editable: bool,
+    /// Coloca as fotos do PDF na resolução original sobre o fundo, em vez de renderizá-las
+    /// com a página (padrão: não; implícito no modo editável)
+    pub native_images: bool,
+    /// Gera cada slide também em SVG vetorial, com a imagem como alternativa (padrão: não)
+    pub vector: bool,
+    /// Formato das imagens das páginas (padrão: PNG)
+    pub encoding: ImageEncoding,
+    /// Limite em bytes para as imagens da apresentação; baixa a qualidade JPEG até caber (padrão: nenhum)
+    pub max_size: Option<u64>,
+    /// Workers para codificar as páginas em paralelo
+    pub jobs: usize,
+    /// Token verificado entre páginas para interromper a conversão
+    pub cancellation: CancellationToken,
+}
+
+impl ConversionRequest {
+    /// Cria uma requisição para um PDF, com o formato deduzido da extensão de `output`
+    pub fn new(input: impl Into<PathBuf>, output: impl Into<PathBuf>) -> Self {
+        let output = output.into();
+        let format = output
+            .extension()
+            .and_then(|e| OutputFormat::from_name(&e.to_string_lossy()))
+            .unwrap_or_default();
+
+        Self {
+            inputs: vec![PdfInput::new(input)],
+            password: None,
+            output,
+            format,
+            keynote_backend: KeynoteBackend::default(),
+            resolution: Resolution::default(),
+            layout: SlideLayout::default(),
+            trim: None,
+            pages: PageSelection::all(),
+            file_sections: false,
+            handout: None,
+            notes: NotesSource::None,
+            beamer_notes: BeamerNotes::Off,
+            overlays: OverlayMode::Off,
+            outline: true,
+            links: true,
+            editable: false,
+            native_images: false,
+            vector: false,
+            encoding: ImageEncoding::default(),
+            max_size: None,
+            jobs: pipeline::default_jobs(),
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_password(mut self, password: Password) -> Self {
+        self.password = Some(password);
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_keynote_backend(mut self, backend: KeynoteBackend) -> Self {
+        self.keynote_backend = backend;
+        self
+    }
+
+    /// Escala de cada página calculada pelo seu tamanho, ex: `Resolution::FULL_HD`
+    pub fn with_resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Resolução fixa, qualquer que seja o tamanho da página
+    pub fn with_dpi(mut self, dpi: u16) -> Self {
+        self.resolution = Resolution::Dpi(dpi);
+        self
+    }
+
+    pub fn with_layout(mut self, layout: SlideLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Recorta cada página na sua área com conteúdo (ou numa caixa do PDF) antes de renderizar
+    pub fn with_trim(mut self, trim: Option<Trim>) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Acrescenta um PDF depois dos anteriores
+    pub fn with_input(mut self, input: PdfInput) -> Self {
+        self.inputs.push(input);
+        self
+    }
+
+    /// Troca a lista de PDFs de entrada
+    pub fn with_inputs(mut self, inputs: Vec<PdfInput>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    pub fn with_pages(mut self, pages: PageSelection) -> Self {
+        self.pages = pages;
+        self
+    }
+
+    pub fn with_file_sections(mut self, file_sections: bool) -> Self {
+        self.file_sections = file_sections;
+        self
+    }
+
+    /// Divide as páginas com vários slides (apostilas N por página) em slides separados
+    pub fn with_handout(mut self, handout: Option<Handout>) -> Self {
+        self.handout = handout;
+        self
+    }
+
+    pub fn with_notes(mut self, notes: NotesSource) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    /// Divide páginas do Beamer com notas ao lado; use com `NotesSource::PageText`
+    /// para levar o texto da metade de notas às notas do apresentador
+    pub fn with_beamer_notes(mut self, beamer_notes: BeamerNotes) -> Self {
+        self.beamer_notes = beamer_notes;
+        self
+    }
+
+    pub fn with_overlays(mut self, overlays: OverlayMode) -> Self {
+        self.overlays = overlays;
+        self
+    }
+
+    pub fn with_outline(mut self, outline: bool) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    pub fn with_links(mut self, links: bool) -> Self {
+        self.links = links;
+        self
+    }
+
+    /// Textos viram caixas de texto e imagens viram objetos; o Keynote nativo mantém as imagens
+    pub fn with_editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    pub fn with_native_images(mut self, native_images: bool) -> Self {
+        self.native_images = native_images;
+        self
+    }
+
+    /// Páginas com construções que o SVG não reproduz ficam só com a imagem, e cada uma
+    /// gera um `ProgressEvent::RasterFallback`
+    pub fn with_vector(mut self, vector: bool) -> Self {
+        self.vector = vector;
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: ImageEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Páginas sem perdas também passam a JPEG se baixar a qualidade das fotos não bastar
+    pub fn with_max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+}
+
+/// Converte um PDF na apresentação descrita por `request`
+pub fn convert(request: &ConversionRequest, progress: &dyn ProgressSink) -> Result<(), Pdf2KeyError> {
+    log::info!("Iniciando conversão para {}", request.output.display());
+
+    // O pacote .key nativo não escreve objetos: a página inteira viraria imagem sem aviso claro
+    if request.format == OutputFormat::Keynote && request.keynote_backend == KeynoteBackend::Native {
+        if request.editable {
+            return Err(Pdf2KeyError::UnsupportedOption { option: "O modo editável", output: "Keynote nativo" });
+        }
+        if request.native_images {
+            return Err(Pdf2KeyError::UnsupportedOption { option: "Imagens na resolução original", output: "Keynote nativo" });
+        }
+    }
+
+    // Carrega PDFium
+    let processor = pdf_processor::PdfProcessor::new()?;
+
+    // Vários PDFs viram um documento só, com as páginas numeradas em sequência
+    let mut merged: Option<pdf_processor::PdfDocumentHandle> = None;
+    let mut page_indices = Vec::new();
+    let mut file_starts = Vec::with_capacity(request.inputs.len());
+    for input in &request.inputs {
+        let password = input.password.as_ref().or(request.password.as_ref());
+        let opened = processor.open_with_password(&input.path, password)?;
+        let selected = opened.select_pages(input.pages.as_ref().unwrap_or(&request.pages))?;
+        let offset = merged.as_ref().map_or(0, |document| document.page_count());
+        if let Some(&first) = selected.first() {
+            file_starts.push((offset + first, input.section_title()));
+        }
+        page_indices.extend(selected.into_iter().map(|index| offset + index));
+        merged = Some(match merged {
+            Some(document) => document.append(opened),
+            None => opened,
+        });
+    }
+    let Some(document) = merged else {
+        return Err(Pdf2KeyError::PdfOpen { path: PathBuf::new(), reason: "nenhum PDF de entrada".to_string() });
+    };
+    // Um PDF sem páginas com a seleção `all` não passa pelo erro de `select_pages`
+    if page_indices.is_empty() {
+        return Err(Pdf2KeyError::InvalidPageSelection(PageSelectionError::nothing_selected(document.page_count())));
+    }
+    if request.inputs.len() > 1 {
+        log::info!("{} arquivos juntados, {} páginas selecionadas", request.inputs.len(), page_indices.len());
+    }
+    let mut document = document.with_beamer_notes(request.beamer_notes);
+
+    // Apostilas: cada célula passa a ser uma página, antes das margens e da renderização
+    if let Some(handout) = &request.handout {
+        let views = handout::page_views(&document, &page_indices, handout, &request.cancellation)?;
+        page_indices = (0..views.len()).collect();
+        document = document.with_page_views(views);
+    }
+    progress.report(&ProgressEvent::Started { total: page_indices.len() });
+
+    // Margens: as regiões saem antes da renderização para valerem também para links e objetos
+    if let Some(trim) = &request.trim {
+        let regions = trim::page_regions(&document, &page_indices, trim, &request.cancellation)?;
+        document = document.with_trim_regions(regions);
+    }
+
+    // Diretório temporário com nome único, apagado ao sair (inclusive após erro, cancelamento ou pânico)
+    let temp_dir = tempfile::Builder::new()
+        .prefix("pdf2key_")
+        .tempdir()
+        .map_err(|source| Pdf2KeyError::Io { path: std::env::temp_dir(), source })?;
+    log::debug!("Diretório temporário: {}", temp_dir.path().display());
+
+    // Início de cada PDF já nos índices finais (com as apostilas divididas)
+    let file_starts: Vec<(usize, String)> = file_starts
+        .into_iter()
+        .filter_map(|(page, title)| Some((document.view_of(page)?, title)))
+        .collect();
+    render_and_build(&document, &page_indices, &file_starts, request, temp_dir.path(), progress)?;
+
+    progress.report(&ProgressEvent::Finished);
+    Ok(())
+}
+
+/// Renderiza as páginas em `temp_dir` e gera a apresentação
+///
+/// O PDFium renderiza uma página por vez nesta thread, enquanto `request.jobs`
+/// workers codificam as imagens em paralelo. Poucas páginas ficam em memória ao
+/// mesmo tempo, independente do tamanho do documento.
+///
+/// Com overlays agrupados, os frames vêm dos rótulos das páginas (`\pause` do Beamer
+/// repete o número do frame) ou, sem rótulos, da comparação de cada página com a anterior.
+/// `file_starts` traz o índice da primeira página de cada PDF e o título da sua seção;
+/// frames nunca atravessam o início de um PDF.
+fn render_and_build(
+    document: &pdf_processor::PdfDocumentHandle,
+    page_indices: &[usize],
+    file_starts: &[(usize, String)],
+    request: &ConversionRequest,
+    temp_dir: &Path,
+    progress: &dyn ProgressSink,
+) -> Result<(), Pdf2KeyError> {
+    let cancellation = &request.cancellation;
+
+    // O pacote .key nativo não escreve SVG (os objetos são recusados em `convert`)
+    let keynote_native = request.format == OutputFormat::Keynote && request.keynote_backend == KeynoteBackend::Native;
+    let editable = request.editable;
+    let native_images = request.native_images || editable;
+    let vector = request.vector && !keynote_native;
+    if request.vector && keynote_native {
+        progress.report(&ProgressEvent::Warning(ConversionWarning::VectorUnsupported));
+    }
+    // Objetos extraídos e camadas de build não se combinam: fica só a última página do frame
+    let overlay_mode = match (native_images, request.overlays) {
+        (true, OverlayMode::Builds) => {
+            progress.report(&ProgressEvent::Warning(ConversionWarning::BuildsUnsupported { reason: "objetos extraídos" }));
+            OverlayMode::LastFrame
+        }
+        (_, mode) => mode,
+    };
+    // Com builds o Keynote via AppleScript passa pelo pacote nativo, que não tem SVG
+    let vector = vector && !(request.format == OutputFormat::Keynote && overlay_mode == OverlayMode::Builds);
+    if request.vector && !vector && !keynote_native {
+        progress.report(&ProgressEvent::Warning(ConversionWarning::VectorUnsupported));
+    }
+
+    let starts_file = |index: usize| file_starts.iter().any(|&(start, _)| start == index);
+    let label_starts = match overlay_mode {
+        OverlayMode::Off => None,
+        _ => {
+            let labels = page_indices
+                .iter()
+                .map(|&index| document.page_label(index))
+                .collect::<Result<Vec<_>, _>>()?;
+            overlays::frame_starts_from_labels(page_indices, &labels).map(|starts| {
+                starts.into_iter().zip(page_indices).map(|(starts, &index)| starts || starts_file(index)).collect::<Vec<_>>()
+            })
+        }
+    };
+
+    // Com rótulos, só a última página de cada frame precisa ser renderizada
+    let (page_indices, label_starts) = match (overlay_mode, label_starts) {
+        (OverlayMode::LastFrame, Some(starts)) => {
+            let (kept, starts) = overlays::last_pages(page_indices, &starts);
+            (kept, Some(starts))
+        }
+        (_, starts) => (page_indices.to_vec(), starts),
+    };
+    let page_indices = page_indices.as_slice();
+    let total_pages = page_indices.len();
+    let slide_notes = notes::collect(&request.notes, document, page_indices)?;
+
+    // Renderiza no tamanho pedido (3840×2160 por padrão) e salva cada imagem.
+    // O cancelamento é verificado antes de cada página.
+    let mut rendered = 0;
+    let mut frame_starts = Vec::with_capacity(total_pages);
+    let mut previous: Option<(Arc<RgbaImage>, Option<Thumbnail>)> = None;
+    let mut fallbacks: Vec<(usize, &'static str)> = Vec::new();
+    let pages = page_indices
+        .iter()
+        .enumerate()
+        .map(|(i, &index)| -> Result<_, Pdf2KeyError> {
+            cancellation.check()?;
+            // Com objetos extraídos a imagem é só o fundo; textos e imagens seguem à parte
+            let (page, objects) = if editable {
+                let page = document.render_editable(index, request.resolution)?;
+                (page.background, page.objects)
+            } else if native_images {
+                let page = document.render_without_images(index, request.resolution)?;
+                (page.background, page.objects)
+            } else {
+                (document.render_page(index, request.resolution)?, Vec::new())
+            };
+            let page = Arc::new(page.into_rgba8());
+            rendered += 1;
+            progress.report(&ProgressEvent::PageRendered { page: index + 1, done: rendered, total: total_pages });
+
+            // A imagem fica como alternativa ao SVG e como o slide das páginas não suportadas
+            let svg = if vector {
+                match document.render_svg(index, request.resolution, !editable, !native_images)? {
+                    VectorPage::Svg(svg) => Some(svg),
+                    VectorPage::Unsupported(reason) => {
+                        fallbacks.push((index + 1, reason));
+                        progress.report(&ProgressEvent::RasterFallback { page: index + 1, reason, done: rendered, total: total_pages });
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            // Sem rótulos, compara miniaturas com a página anterior
+            let thumbnail = (overlay_mode != OverlayMode::Off && label_starts.is_none()).then(|| Thumbnail::new(&page));
+            let starts_frame = match (&label_starts, &previous, &thumbnail) {
+                (Some(starts), _, _) => starts[i],
+                _ if starts_file(index) => true,
+                (None, Some((_, Some(before))), Some(after)) => !before.continues_into(after),
+                _ => true,
+            };
+            frame_starts.push(starts_frame);
+
+            // Em builds, o worker precisa da página anterior do frame para extrair a camada
+            let layer_base = match (overlay_mode, starts_frame, &previous) {
+                (OverlayMode::Builds, false, Some((before, _))) => Some(Arc::clone(before)),
+                _ => None,
+            };
+            previous = Some((Arc::clone(&page), thumbnail));
+            Ok(RenderedPage { image: page, layer_base, objects, svg })
+        });
+    let rendered_pages = pipeline::run_ordered(
+        pages,
+        request.jobs,
+        |i, RenderedPage { image: img, layer_base, objects, svg }| {
+            let encode_error = |source| Pdf2KeyError::ImageEncode { page: page_indices[i] + 1, source };
+            let encoding = request.encoding.choose(&img);
+            let img_path = temp_dir.join(format!("slide_{:04}.{}", i, encoding.extension()));
+            encoding.save(&img, &img_path).map_err(encode_error)?;
+            // Com limite de tamanho, o original sem perdas permite recomprimir depois
+            if request.max_size.is_some() && encoding.is_lossy() {
+                encoding::save_master(&img, &img_path).map_err(encode_error)?;
+            }
+
+            let vector_path = match svg {
+                Some(svg) => {
+                    let path = temp_dir.join(format!("slide_{:04}.svg", i));
+                    std::fs::write(&path, svg).map_err(|source| Pdf2KeyError::Io { path: path.clone(), source })?;
+                    Some(path)
+                }
+                None => None,
+            };
+
+            let layer = layer_base.and_then(|before| overlays::build_layer(&before, &img));
+            let layer_path = match layer {
+                Some(layer) => {
+                    let layer_path = temp_dir.join(format!("layer_{:04}.png", i));
+                    layer.save_with_format(&layer_path, ImageFormat::Png).map_err(encode_error)?;
+                    Some(layer_path)
+                }
+                None => None,
+            };
+
+            // Imagens extraídas da página (modo editável ou resolução original), gravadas ao lado dela
+            let objects = objects
+                .into_iter()
+                .enumerate()
+                .map(|(k, object)| match object {
+                    PageObject::Text(text) => Ok(SlideObject::Text(text)),
+                    PageObject::Image(image) => {
+                        let path = temp_dir.join(format!("object_{:04}_{:02}.{}", i, k, image.extension()));
+                        image.save(&path).map_err(encode_error)?;
+                        Ok(SlideObject::Image(PlacedImage { rect: image.rect, path }))
+                    }
+                })
+                .collect::<Result<Vec<_>, Pdf2KeyError>>()?;
+            Ok(EncodedPage { image: img_path, layer: layer_path, objects, vector: vector_path })
+        },
+        |i, done| {
+            progress.report(&ProgressEvent::PageEncoded { page: page_indices[i] + 1, done, total: total_pages });
+        },
+    )?;
+    cancellation.check()?;
+    if !fallbacks.is_empty() {
+        let pages: Vec<String> = fallbacks.iter().map(|(page, reason)| format!("{} ({})", page, reason)).collect();
+        log::info!("{} de {} páginas rasterizadas: {}", fallbacks.len(), total_pages, pages.join(", "));
+    }
+
+    let (mut slides, slide_pages) = assemble_slides(overlay_mode, page_indices, rendered_pages, slide_notes, &frame_starts);
+    if slides.len() != total_pages {
+        log::info!("{} páginas agrupadas em {} slides", total_pages, slides.len());
+    }
+
+    // Nomes e seções a partir do sumário e dos rótulos das páginas
+    if request.outline {
+        let labels = slide_pages
+            .iter()
+            .map(|&(first, _)| document.page_label(first))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outline_entries = document.outline();
+        outline::apply(&mut slides, &slide_pages, &outline_entries, &labels);
+        let sections = slides.iter().filter(|s| s.section.is_some()).count();
+        if sections > 0 && !request.file_sections {
+            log::info!("{} seções, {} entradas no sumário", sections, outline_entries.len());
+        }
+    }
+
+    // Uma seção por PDF, no primeiro slide que mostra páginas dele
+    if request.file_sections {
+        for slide in slides.iter_mut() {
+            slide.section = None;
+        }
+        for (start, title) in file_starts {
+            if let Some(i) = slide_pages.iter().position(|&(_, last)| last >= *start) {
+                slides[i].section = Some(title.clone());
+            }
+        }
+        log::info!("{} seções, uma por PDF", file_starts.len());
+    }
+
+    // Links da última página de cada slide (o estado final de um frame de overlay)
+    if request.links {
+        let mut total_links = 0;
+        for (slide, &(_, last)) in slides.iter_mut().zip(&slide_pages) {
+            slide.links = links::resolve(document.page_links(last, request.resolution)?, &slide_pages);
+            total_links += slide.links.len();
+        }
+        if total_links > 0 && request.format == OutputFormat::Keynote {
+            progress.report(&ProgressEvent::Warning(ConversionWarning::LinksDropped { count: total_links }));
+        } else if total_links > 0 {
+            log::info!("{} links preservados", total_links);
+        }
+    }
+    let total_slides = slides.len();
+
+    if let Some(max_size) = request.max_size {
+        let last_pages: Vec<usize> = slide_pages.iter().map(|&(_, last)| last + 1).collect();
+        let size = encoding::fit_budget(&mut slides, &last_pages, max_size, request.encoding, request.jobs)?;
+        log::info!("Imagens com {:.1} MB para o limite de {:.1} MB", size as f64 / 1e6, max_size as f64 / 1e6);
+    }
+
+    // Cada slide gravado é reportado; o cancelamento interrompe o backend
+    let mut on_slide = |done: usize| -> anyhow::Result<()> {
+        cancellation.check()?;
+        progress.report(&ProgressEvent::SlideBuilt { done, total: total_slides });
+        Ok(())
+    };
+    
+    // Gera a apresentação no formato escolhido
+    let output_path = request.output.as_path();
+    let result = match request.format {
+        OutputFormat::Keynote => {
+            let mut builder = keynote::KeynoteBuilder::new()
+                .with_backend(request.keynote_backend)
+                .with_layout(request.layout);
+            for slide in slides {
+                builder.push_slide(slide);
+            }
+            builder.build_with_progress(output_path, &mut on_slide)
+        }
+        OutputFormat::Pptx => {
+            let mut builder = pptx::PptxBuilder::new().with_layout(request.layout);
+            for slide in slides {
+                builder.push_slide(slide);
+            }
+            builder.build_with_progress(output_path, &mut on_slide)
+        }
+        OutputFormat::Odp => {
+            let (width, height) = document.page_size(page_indices[0])?;
+            let mut builder = odp::OdpBuilder::new()
+                .with_page_size(width, height)
+                .with_layout(request.layout);
+            for slide in slides {
+                builder.push_slide(slide);
+            }
+            builder.build_with_progress(output_path, &mut on_slide)
+        }
+    };
+    
+    result.map_err(|e| {
+        // Erros tipados (cancelamento, falha do osascript) passam adiante como estão
+        let error = e.downcast::<Pdf2KeyError>().unwrap_or_else(|e| Pdf2KeyError::BackendFailed {
+            format: request.format,
+            stderr: format!("{:#}", e),
+        });
+        if matches!(error, Pdf2KeyError::Cancelled) {
+            // Não deixa uma apresentação incompleta para trás
+            let _ = std::fs::remove_file(output_path);
+        }
+        error
+    })?;
+
+    // O limite vale para o arquivo gravado, não só para as imagens
+    if let (Some(limit), Ok(metadata)) = (request.max_size, std::fs::metadata(output_path)) {
+        if metadata.is_file() && metadata.len() > limit {
+            progress.report(&ProgressEvent::Warning(ConversionWarning::SizeLimitExceeded { size: metadata.len(), limit }));
+        }
+    }
+    Ok(())
+}
+
+/// Página renderizada, a caminho do worker que grava os arquivos
+struct RenderedPage {
+    image: Arc<RgbaImage>,
+    /// Página anterior do frame, para extrair a camada de build
+    layer_base: Option<Arc<RgbaImage>>,
+    objects: Vec<PageObject>,
+    svg: Option<String>,
+}
+
+/// Arquivos gravados de uma página
+struct EncodedPage {
+    image: PathBuf,
+    layer: Option<PathBuf>,
+    objects: Vec<SlideObject>,
+    vector: Option<PathBuf>,
+}
+
+/// Monta os slides a partir das páginas renderizadas, agrupando os frames de overlay
+///
+/// As notas de um frame agrupado são as da sua última página. Retorna também a
+/// primeira e a última página do documento mostradas por cada slide.
+fn assemble_slides(
+    mode: OverlayMode,
+    page_indices: &[usize],
+    pages: Vec<EncodedPage>,
+    notes: Vec<String>,
+    frame_starts: &[bool],
+) -> (Vec<Slide>, Vec<(usize, usize)>) {
+    let mut slides: Vec<Slide> = Vec::new();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (((&starts, &index), notes), page) in frame_starts.iter().zip(page_indices).zip(notes).zip(pages) {
+        let EncodedPage { image, layer, objects, vector } = page;
+        match (slides.last_mut(), ranges.last_mut()) {
+            (Some(slide), Some(range)) if !starts && mode != OverlayMode::Off => {
+                match mode {
+                    OverlayMode::LastFrame => {
+                        slide.image = image;
+                        slide.vector = vector;
+                        slide.objects = objects;
+                    }
+                    _ => slide.builds.extend(layer),
+                }
+                slide.notes = notes;
+                range.1 = index;
+            }
+            _ => {
+                let mut slide = Slide::new(&image).with_notes(notes).with_objects(objects);
+                slide.vector = vector;
+                slides.push(slide);
+                ranges.push((index, index));
+            }
+        }
+    }
+    (slides, ranges)
+}
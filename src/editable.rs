@@ -0,0 +1,109 @@
+//! Modo editável: textos e imagens da página como objetos do slide
+//! O restante (vetores, fundos) vira a imagem de fundo, sem os textos e imagens
+
+use crate::images::EmbeddedImage;
+use crate::links::PixelRect;
+use image::DynamicImage;
+use std::path::PathBuf;
+
+/// Trecho de texto posicionado, com o estilo da fonte do PDF
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBox {
+    /// Área ocupada pelo texto, em pixels da imagem do slide
+    pub rect: PixelRect,
+    pub text: String,
+    /// Família da fonte, sem prefixo de subconjunto nem estilo (ex: "Helvetica")
+    pub font: String,
+    /// Tamanho da fonte em pixels da imagem do slide
+    pub size: f32,
+    pub color: [u8; 3],
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// Imagem do PDF posicionada sobre o fundo do slide
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedImage {
+    /// Área ocupada pela imagem, em pixels da imagem do slide
+    pub rect: PixelRect,
+    pub path: PathBuf,
+}
+
+/// Objeto editável de um slide, na ordem de pintura do PDF
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlideObject {
+    Text(TextBox),
+    Image(PlacedImage),
+}
+
+/// Objeto extraído de uma página, antes de a imagem ser gravada
+pub enum PageObject {
+    Text(TextBox),
+    Image(EmbeddedImage),
+}
+
+/// Página decomposta: fundo sem textos e imagens, e os objetos removidos
+pub struct EditablePage {
+    pub background: DynamicImage,
+    pub objects: Vec<PageObject>,
+}
+
+/// Junta trechos vizinhos na mesma linha e com o mesmo estilo
+///
+/// O PDF costuma ter um objeto de texto por palavra ou por glifo; editar fica mais
+/// simples com uma caixa por linha.
+pub(crate) fn merge_runs(objects: Vec<PageObject>) -> Vec<PageObject> {
+    let mut merged: Vec<PageObject> = Vec::with_capacity(objects.len());
+    for object in objects {
+        if let (Some(PageObject::Text(previous)), PageObject::Text(next)) = (merged.last_mut(), &object) {
+            if continues_line(previous, next) {
+                let gap = next.rect.x as f32 - (previous.rect.x + previous.rect.width) as f32;
+                if gap > next.size * 0.15 && !previous.text.ends_with(' ') && !next.text.starts_with(' ') {
+                    previous.text.push(' ');
+                }
+                previous.text.push_str(&next.text);
+                let right = (previous.rect.x + previous.rect.width).max(next.rect.x + next.rect.width);
+                let bottom = (previous.rect.y + previous.rect.height).max(next.rect.y + next.rect.height);
+                previous.rect.y = previous.rect.y.min(next.rect.y);
+                previous.rect.width = right - previous.rect.x;
+                previous.rect.height = bottom - previous.rect.y;
+                continue;
+            }
+        }
+        merged.push(object);
+    }
+    merged
+}
+
+fn continues_line(previous: &TextBox, next: &TextBox) -> bool {
+    let same_style = previous.font == next.font
+        && (previous.size - next.size).abs() < 0.5
+        && previous.color == next.color
+        && previous.bold == next.bold
+        && previous.italic == next.italic;
+    let baseline = (previous.rect.y + previous.rect.height) as f32;
+    let next_baseline = (next.rect.y + next.rect.height) as f32;
+    let gap = next.rect.x as f32 - (previous.rect.x + previous.rect.width) as f32;
+    same_style && (baseline - next_baseline).abs() < next.size * 0.3 && gap > -next.size * 0.2 && gap < next.size
+}
+
+/// Nome da família a partir do nome da fonte no PDF ("ABCDEF+Helvetica-BoldOblique" -> "Helvetica")
+pub(crate) fn font_family(name: &str) -> String {
+    let name = match name.split_once('+') {
+        Some((prefix, rest)) if prefix.len() == 6 && prefix.chars().all(|c| c.is_ascii_uppercase()) => rest,
+        _ => name,
+    };
+    let family = name.split([',', '-']).next().unwrap_or(name);
+    // Nomes PostScript sem separador: "TimesNewRomanPSMT", "ArialMT"
+    let family = family.trim_end_matches("PSMT").trim_end_matches("MT");
+    if family.is_empty() { name.to_string() } else { family.to_string() }
+}
+
+/// Estilo indicado pelo nome da fonte: (negrito, itálico)
+pub(crate) fn font_style(name: &str) -> (bool, bool) {
+    // O estilo vem depois do separador ("Helvetica-BoldOblique"); sem ele, o nome inteiro
+    let style = name.split_once(['-', ',']).map_or(name, |(_, style)| style).to_ascii_lowercase();
+    let bold = ["bold", "black", "heavy", "demi"].iter().any(|w| style.contains(w));
+    let italic = style.contains("italic") || style.contains("oblique");
+    (bold, italic)
+}
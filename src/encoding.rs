@@ -0,0 +1,423 @@
+//! Codificação das imagens dos slides: PNG, JPEG ou WebP
+//! PNG mantém texto e desenhos nítidos mas deixa fotos enormes; o modo automático escolhe
+//! por página, e o limite de tamanho baixa a qualidade JPEG (ou WebP) até as imagens caberem.
+
+use crate::error::Pdf2KeyError;
+use crate::pipeline;
+use crate::slide::Slide;
+use image::buffer::ConvertBuffer;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::{ImageEncoder, ImageResult, RgbImage, RgbaImage};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Qualidade JPEG padrão
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+/// Qualidades tentadas em ordem pelo limite de tamanho, abaixo da qualidade pedida
+const QUALITY_STEPS: [u8; 7] = [85, 75, 65, 55, 45, 35, 25];
+
+/// Reserva do limite de tamanho para o que não é imagem: miniaturas, metadados e o XML de cada slide
+const PACKAGE_OVERHEAD: u64 = 256 * 1024;
+const SLIDE_OVERHEAD: u64 = 8 * 1024;
+/// Folga, em porcentagem do limite, para o erro da estimativa (cabeçalhos do zip, compressão)
+const SAFETY_MARGIN_PERCENT: u64 = 5;
+
+/// Fração de pixels diferentes do vizinho a partir da qual a página é tratada como foto
+///
+/// Texto e desenhos têm grandes áreas de cor chapada (só as bordas suavizadas variam);
+/// fotos e degradês variam quase pixel a pixel.
+const PHOTO_MIN_VARYING: f32 = 0.2;
+
+/// Como as imagens das páginas são gravadas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageEncoding {
+    /// Sem perdas (padrão)
+    #[default]
+    Png,
+    /// Com perdas, `quality` de 1 a 100
+    Jpeg { quality: u8 },
+    /// WebP, em geral menor que o PNG: sem perdas sem `quality`, com perdas com `quality` de 1 a 100
+    ///
+    /// O codificador disponível só grava WebP sem perdas: o modo com perdas reduz os níveis de
+    /// cada canal conforme a qualidade antes de codificar (preto e branco ficam exatos).
+    WebP { quality: Option<u8> },
+    /// PNG nas páginas de texto e desenho, JPEG com `quality` nas páginas com fotos
+    Auto { quality: u8 },
+}
+
+impl ImageEncoding {
+    /// Interpreta `png`, `webp[:qualidade]`, `jpeg[:qualidade]` ou `auto[:qualidade]`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (kind, quality) = match name.split_once(':') {
+            Some((kind, quality)) => (kind, Some(quality.parse::<u8>().ok().filter(|q| (1..=100).contains(q))?)),
+            None => (name, None),
+        };
+        match kind.to_ascii_lowercase().as_str() {
+            "png" if quality.is_none() => Some(ImageEncoding::Png),
+            "webp" => Some(ImageEncoding::WebP { quality }),
+            "jpeg" | "jpg" => Some(ImageEncoding::Jpeg { quality: quality.unwrap_or(DEFAULT_JPEG_QUALITY) }),
+            "auto" => Some(ImageEncoding::Auto { quality: quality.unwrap_or(DEFAULT_JPEG_QUALITY) }),
+            _ => None,
+        }
+    }
+
+    /// Qualidade inicial quando o limite de tamanho obriga a usar perdas
+    pub fn quality(self) -> u8 {
+        match self {
+            ImageEncoding::Jpeg { quality } | ImageEncoding::Auto { quality } => quality,
+            ImageEncoding::WebP { quality: Some(quality) } => quality,
+            ImageEncoding::Png | ImageEncoding::WebP { quality: None } => DEFAULT_JPEG_QUALITY,
+        }
+    }
+
+    /// Formato das páginas recomprimidas pelo limite de tamanho na qualidade `quality`
+    fn lossy(self, quality: u8) -> PageEncoding {
+        match self {
+            ImageEncoding::WebP { .. } => PageEncoding::WebP(Some(quality)),
+            _ => PageEncoding::Jpeg(quality),
+        }
+    }
+
+    /// Formato da imagem de uma página
+    pub(crate) fn choose(self, page: &RgbaImage) -> PageEncoding {
+        match self {
+            ImageEncoding::Png => PageEncoding::Png,
+            ImageEncoding::Jpeg { quality } => PageEncoding::Jpeg(quality),
+            ImageEncoding::WebP { quality } => PageEncoding::WebP(quality),
+            ImageEncoding::Auto { quality } if is_photographic(page) => PageEncoding::Jpeg(quality),
+            ImageEncoding::Auto { .. } => PageEncoding::Png,
+        }
+    }
+}
+
+/// Formato escolhido para uma imagem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PageEncoding {
+    Png,
+    Jpeg(u8),
+    WebP(Option<u8>),
+}
+
+impl PageEncoding {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            PageEncoding::Png => "png",
+            PageEncoding::Jpeg(_) => "jpg",
+            PageEncoding::WebP(_) => "webp",
+        }
+    }
+
+    pub(crate) fn is_lossy(self) -> bool {
+        matches!(self, PageEncoding::Jpeg(_) | PageEncoding::WebP(Some(_)))
+    }
+
+    /// Grava a página; o JPEG descarta o canal alfa (as páginas renderizadas são opacas)
+    pub(crate) fn save(self, image: &RgbaImage, path: &Path) -> ImageResult<()> {
+        let file = std::fs::File::create(path).map_err(image::ImageError::IoError)?;
+        let writer = BufWriter::new(file);
+        let (width, height) = image.dimensions();
+        match self {
+            PageEncoding::Png => PngEncoder::new(writer).write_image(image, width, height, image::ExtendedColorType::Rgba8),
+            PageEncoding::Jpeg(quality) => {
+                let rgb: RgbImage = image.convert();
+                JpegEncoder::new_with_quality(writer, quality).write_image(&rgb, width, height, image::ExtendedColorType::Rgb8)
+            }
+            PageEncoding::WebP(None) => {
+                WebPEncoder::new_lossless(writer).write_image(image, width, height, image::ExtendedColorType::Rgba8)
+            }
+            PageEncoding::WebP(Some(quality)) => {
+                let reduced = reduce_levels(image, quality);
+                WebPEncoder::new_lossless(writer).write_image(&reduced, width, height, image::ExtendedColorType::Rgba8)
+            }
+        }
+    }
+}
+
+/// Níveis por canal do WebP com perdas: 256 em 100 e 16 na qualidade mínima
+fn levels(quality: u8) -> u32 {
+    let bits = 4 + u32::from(quality.clamp(1, 100)) * 4 / 100;
+    1 << bits
+}
+
+/// Reduz cada canal de cor a `levels(quality)` níveis espaçados de 0 a 255
+///
+/// Menos cores distintas deixam o WebP sem perdas bem menor; como os extremos são níveis,
+/// texto preto em fundo branco continua nítido. O canal alfa não muda.
+fn reduce_levels(image: &RgbaImage, quality: u8) -> RgbaImage {
+    let steps = levels(quality) - 1;
+    let table: Vec<u8> = (0..=255u32)
+        .map(|v| {
+            let level = (v * steps + 127) / 255;
+            ((level * 255 + steps / 2) / steps) as u8
+        })
+        .collect();
+    let mut reduced = image.clone();
+    for pixel in reduced.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = table[*channel as usize];
+        }
+    }
+    reduced
+}
+
+/// Original sem perdas de uma página gravada com perdas, para recomprimir com o limite de tamanho
+pub(crate) fn master_path(image: &Path) -> PathBuf {
+    image.with_extension("master.png")
+}
+
+/// Grava o original sem perdas de `image` (compressão rápida: é só um arquivo temporário)
+pub(crate) fn save_master(page: &RgbaImage, image: &Path) -> ImageResult<()> {
+    let file = std::fs::File::create(master_path(image)).map_err(image::ImageError::IoError)?;
+    let (width, height) = page.dimensions();
+    PngEncoder::new_with_quality(BufWriter::new(file), CompressionType::Fast, FilterType::Sub).write_image(
+        page,
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+    )
+}
+
+/// A página parece ter fotos: muitos pixels diferentes do vizinho à direita
+fn is_photographic(page: &RgbaImage) -> bool {
+    let row_bytes = page.width() as usize * 4;
+    let mut varying = 0usize;
+    let mut total = 0usize;
+    // Uma linha a cada 4 basta para a estimativa
+    for row in page.as_raw().chunks_exact(row_bytes.max(4)).step_by(4) {
+        for pair in row.windows(8).step_by(4) {
+            total += 1;
+            if pair[..4] != pair[4..] {
+                varying += 1;
+            }
+        }
+    }
+    total > 0 && varying as f32 / total as f32 >= PHOTO_MIN_VARYING
+}
+
+/// Tamanho de um arquivo, ou zero se ele não existe
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Soma dos arquivos de imagem dos slides (páginas, camadas, objetos e SVGs)
+pub(crate) fn slides_size(slides: &[Slide]) -> u64 {
+    slides
+        .iter()
+        .map(|slide| {
+            let objects = slide.objects.iter().filter_map(|object| match object {
+                crate::editable::SlideObject::Image(placed) => Some(&placed.path),
+                crate::editable::SlideObject::Text(_) => None,
+            });
+            std::iter::once(&slide.image)
+                .chain(&slide.builds)
+                .chain(objects)
+                .chain(&slide.vector)
+                .map(|path| file_size(path))
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+/// Recomprime as imagens dos slides com perdas até a apresentação caber em `max_bytes`
+///
+/// As imagens têm de caber no limite menos uma reserva para o resto do pacote (XML, notas,
+/// miniaturas) e uma folga. A cada passo de qualidade todas as páginas descem juntas: as que
+/// já têm perdas passam à qualidade do passo e as sem perdas passam a ter perdas quando ficam
+/// menores. Com `encoding` WebP os passos são em WebP com perdas; nos outros, em JPEG, a
+/// partir de `encoding.quality()`. Camadas de build, objetos e SVGs não mudam. `pages` dá a
+/// página (1-based) de cada slide, para os erros. Retorna o total das imagens, que ainda pode
+/// passar do limite na qualidade mínima; o tamanho real só se sabe depois de gravar a apresentação.
+pub(crate) fn fit_budget(
+    slides: &mut [Slide],
+    pages: &[usize],
+    max_bytes: u64,
+    encoding: ImageEncoding,
+    jobs: usize,
+) -> Result<u64, Pdf2KeyError> {
+    let quality = encoding.quality();
+    let notes: u64 = slides.iter().map(|slide| slide.notes.len() as u64).sum();
+    let overhead = PACKAGE_OVERHEAD + slides.len() as u64 * SLIDE_OVERHEAD + notes;
+    let budget = max_bytes.saturating_sub(overhead) / 100 * (100 - SAFETY_MARGIN_PERCENT);
+    let mut total = slides_size(slides);
+    if total <= budget {
+        return Ok(total);
+    }
+
+    // Original de cada slide: o próprio arquivo se não tem perdas (as com perdas têm o seu)
+    let originals: Vec<PathBuf> = slides.iter().map(|slide| slide.image.clone()).collect();
+    let lossy: Vec<bool> = originals.iter().map(|image| master_path(image).exists()).collect();
+    let masters: Vec<PathBuf> = originals
+        .iter()
+        .zip(&lossy)
+        .map(|(image, &lossy)| if lossy { master_path(image) } else { image.clone() })
+        .collect();
+    let steps: Vec<u8> = std::iter::once(quality).chain(QUALITY_STEPS.into_iter().filter(|&q| q < quality)).collect();
+
+    for &step in &steps {
+        // As páginas com perdas já estão na qualidade pedida
+        let candidates: Vec<usize> = (0..slides.len()).filter(|&i| !lossy[i] || step < quality).collect();
+        let encoded = pipeline::run_ordered(
+            candidates.into_iter().map(Ok::<_, Pdf2KeyError>),
+            jobs,
+            |_, i| {
+                let encode_error = |source| Pdf2KeyError::ImageEncode { page: pages[i], source };
+                let master = image::open(&masters[i]).map_err(encode_error)?.to_rgba8();
+                let page_encoding = encoding.lossy(step);
+                let path = originals[i].with_extension(format!("q{}.{}", step, page_encoding.extension()));
+                page_encoding.save(&master, &path).map_err(encode_error)?;
+                // Texto e desenhos podem ficar maiores com perdas: aí a página sem perdas fica
+                if !lossy[i] && file_size(&path) >= file_size(&originals[i]) {
+                    return Ok((i, originals[i].clone()));
+                }
+                Ok((i, path))
+            },
+            |_, _| {},
+        )?;
+        for (i, path) in encoded {
+            slides[i].image = path;
+        }
+        total = slides_size(slides);
+        log::debug!("Qualidade {}: imagens com {:.1} MB", step, total as f64 / 1e6);
+        if total <= budget {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn webp_names() {
+        assert_eq!(ImageEncoding::from_name("webp"), Some(ImageEncoding::WebP { quality: None }));
+        assert_eq!(ImageEncoding::from_name("WebP:60"), Some(ImageEncoding::WebP { quality: Some(60) }));
+        assert_eq!(ImageEncoding::from_name("webp:0"), None);
+        assert_eq!(ImageEncoding::from_name("png:80"), None);
+        assert_eq!(ImageEncoding::WebP { quality: Some(60) }.quality(), 60);
+        assert!(PageEncoding::WebP(Some(60)).is_lossy());
+        assert!(!PageEncoding::WebP(None).is_lossy());
+    }
+
+    #[test]
+    fn reduced_levels_keep_black_and_white() {
+        assert_eq!(levels(100), 256);
+        assert_eq!(levels(1), 16);
+        let mut page = RgbaImage::from_pixel(3, 1, Rgba([255, 255, 255, 255]));
+        page.put_pixel(1, 0, Rgba([0, 0, 0, 255]));
+        page.put_pixel(2, 0, Rgba([100, 130, 7, 128]));
+        assert_eq!(reduce_levels(&page, 100), page);
+
+        let reduced = reduce_levels(&page, 25);
+        assert_eq!(reduced.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(reduced.get_pixel(1, 0), &Rgba([0, 0, 0, 255]));
+        // 32 níveis: múltiplos de 255/31, e o alfa fica
+        assert_eq!(reduced.get_pixel(2, 0), &Rgba([99, 132, 8, 128]));
+    }
+
+    /// Página com ruído (pseudoaleatório, reproduzível) sobre um degradê, como uma foto
+    fn photo(width: u32, height: u32, seed: u32) -> RgbaImage {
+        let mut state = seed;
+        RgbaImage::from_fn(width, height, |x, _| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let noise = (state >> 16) as u8 % 64;
+            let base = (x * 160 / width) as u8;
+            Rgba([base + noise, 96 + noise, 191 - noise, 255])
+        })
+    }
+
+    /// Página branca com linhas de "texto" pretas
+    fn text_page() -> RgbaImage {
+        RgbaImage::from_fn(400, 300, |x, y| {
+            if (40..360).contains(&x) && y % 20 < 4 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) }
+        })
+    }
+
+    #[test]
+    fn photographic_pages() {
+        assert!(is_photographic(&photo(200, 150, 1)));
+        assert!(!is_photographic(&text_page()));
+        assert!(!is_photographic(&RgbaImage::new(0, 0)));
+    }
+
+    #[test]
+    fn auto_picks_per_page() {
+        let auto = ImageEncoding::Auto { quality: 70 };
+        assert_eq!(auto.choose(&photo(200, 150, 1)), PageEncoding::Jpeg(70));
+        assert_eq!(auto.choose(&text_page()), PageEncoding::Png);
+        assert_eq!(ImageEncoding::Png.choose(&photo(200, 150, 1)), PageEncoding::Png);
+        assert_eq!(ImageEncoding::Jpeg { quality: 60 }.choose(&text_page()), PageEncoding::Jpeg(60));
+        assert_eq!(ImageEncoding::WebP { quality: Some(60) }.choose(&text_page()), PageEncoding::WebP(Some(60)));
+    }
+
+    /// Deck de fotos gravadas com `encoding` e o limite que só cabe abaixo da qualidade inicial
+    fn over_budget(dir: &Path, encoding: ImageEncoding) -> (Vec<Slide>, u64, u64) {
+        let mut slides = Vec::new();
+        let (mut initial, mut minimum) = (0, 0);
+        for i in 0..3 {
+            let page = photo(320, 240, i + 1);
+            let chosen = encoding.choose(&page);
+            let path = dir.join(format!("slide_{}.{}", i, chosen.extension()));
+            chosen.save(&page, &path).unwrap();
+            if chosen.is_lossy() {
+                save_master(&page, &path).unwrap();
+            }
+            for (quality, size) in [(encoding.quality(), &mut initial), (QUALITY_STEPS[6], &mut minimum)] {
+                let probe = dir.join(format!("probe_{}_{}.{}", i, quality, chosen.extension()));
+                encoding.lossy(quality).save(&page, &probe).unwrap();
+                *size += file_size(&probe);
+            }
+            slides.push(Slide::new(&path));
+        }
+        // Imagens entre a qualidade mínima e a inicial
+        let images = (initial + minimum) / 2;
+        let overhead = PACKAGE_OVERHEAD + 3 * SLIDE_OVERHEAD;
+        let max_bytes = overhead + images * 100 / (100 - SAFETY_MARGIN_PERCENT) + 100;
+        (slides, max_bytes, images)
+    }
+
+    #[test]
+    fn jpeg_deck_steps_quality_down_until_it_fits() {
+        let dir = tempfile::tempdir().unwrap();
+        let encoding = ImageEncoding::Jpeg { quality: 85 };
+        let (mut slides, max_bytes, images) = over_budget(dir.path(), encoding);
+        assert!(slides_size(&slides) > images);
+
+        let total = fit_budget(&mut slides, &[1, 2, 3], max_bytes, encoding, 2).unwrap();
+        assert!(total <= images, "{} > {}", total, images);
+        assert_eq!(total, slides_size(&slides));
+        for slide in &slides {
+            let name = slide.image.file_name().unwrap().to_string_lossy().into_owned();
+            assert!(name.ends_with(".jpg") && name.contains(".q") && !name.contains(".q85."), "{}", name);
+        }
+    }
+
+    #[test]
+    fn webp_deck_steps_through_lossy_webp() {
+        let dir = tempfile::tempdir().unwrap();
+        let encoding = ImageEncoding::WebP { quality: None };
+        let (mut slides, max_bytes, images) = over_budget(dir.path(), encoding);
+        assert!(slides_size(&slides) > images);
+
+        let total = fit_budget(&mut slides, &[1, 2, 3], max_bytes, encoding, 1).unwrap();
+        assert!(total <= images, "{} > {}", total, images);
+        assert!(slides.iter().all(|slide| slide.image.to_string_lossy().ends_with(".webp")));
+        assert!(slides.iter().any(|slide| slide.image.to_string_lossy().contains(".q")));
+    }
+
+    #[test]
+    fn deck_within_budget_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slide.png");
+        PageEncoding::Png.save(&text_page(), &path).unwrap();
+        let mut slides = vec![Slide::new(&path)];
+        let total = fit_budget(&mut slides, &[1], 10 * 1024 * 1024, ImageEncoding::Png, 1).unwrap();
+        assert_eq!(total, file_size(&path));
+        assert_eq!(slides[0].image, path);
+    }
+}
@@ -0,0 +1,92 @@
+//! Erros da conversão
+//! Cada falha tem sua variante, para que a interface gráfica, a linha de comando e
+//! outras ferramentas possam reagir a ela (ex: pedir a senha do PDF)
+
+use crate::conversion::OutputFormat;
+use crate::page_selection::PageSelectionError;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Falha ao converter um PDF
+#[derive(Debug)]
+pub enum Pdf2KeyError {
+    /// A biblioteca PDFium não foi encontrada em nenhum dos locais procurados
+    PdfiumNotFound { searched: Vec<PathBuf> },
+    /// O arquivo não pôde ser aberto como PDF
+    PdfOpen { path: PathBuf, reason: String },
+    /// O PDF é protegido por senha e nenhuma foi informada
+    PasswordRequired { path: PathBuf },
+    /// A senha informada não abre o PDF
+    IncorrectPassword { path: PathBuf },
+    /// A seleção de páginas não se aplica ao documento
+    InvalidPageSelection(PageSelectionError),
+    /// Falha do PDFium ao ler ou renderizar uma página (1-based)
+    PageRender { page: usize, reason: String },
+    /// Falha do PDFium ao extrair o texto de uma página (1-based)
+    PageText { page: usize, reason: String },
+    /// Falha ao codificar ou gravar a imagem de uma página (1-based)
+    ImageEncode { page: usize, source: image::ImageError },
+    /// Falha de E/S fora das páginas (ex: diretório temporário)
+    Io { path: PathBuf, source: std::io::Error },
+    /// O backend de saída falhou; `stderr` traz a saída do osascript ou a descrição da falha
+    BackendFailed { format: OutputFormat, stderr: String },
+    /// A saída escolhida não suporta uma opção pedida (ex: objetos editáveis no Keynote nativo)
+    UnsupportedOption { option: &'static str, output: &'static str },
+    /// Conversão cancelada pelo usuário
+    Cancelled,
+}
+
+impl fmt::Display for Pdf2KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pdf2KeyError::PdfiumNotFound { searched } => {
+                let searched: Vec<String> = searched.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "Não foi possível encontrar a biblioteca PDFium (procurada em: {})", searched.join(", "))
+            }
+            Pdf2KeyError::PdfOpen { path, reason } => {
+                write!(f, "Falha ao abrir o arquivo PDF {}: {}", path.display(), reason)
+            }
+            Pdf2KeyError::PasswordRequired { path } => {
+                write!(f, "O PDF {} é protegido por senha", path.display())
+            }
+            Pdf2KeyError::IncorrectPassword { path } => {
+                write!(f, "Senha incorreta para o PDF {}", path.display())
+            }
+            Pdf2KeyError::InvalidPageSelection(e) => write!(f, "Seleção de páginas inválida: {}", e),
+            Pdf2KeyError::PageRender { page, reason } => {
+                write!(f, "Falha ao renderizar a página {}: {}", page, reason)
+            }
+            Pdf2KeyError::PageText { page, reason } => {
+                write!(f, "Falha ao extrair o texto da página {}: {}", page, reason)
+            }
+            Pdf2KeyError::ImageEncode { page, source } => {
+                write!(f, "Falha ao gravar a imagem da página {}: {}", page, source)
+            }
+            Pdf2KeyError::Io { path, source } => write!(f, "Falha de E/S em {}: {}", path.display(), source),
+            Pdf2KeyError::BackendFailed { format, stderr } => {
+                write!(f, "Falha ao gerar a apresentação {}: {}", format.label(), stderr.trim())
+            }
+            Pdf2KeyError::UnsupportedOption { option, output } => {
+                write!(f, "{} não está disponível no {}", option, output)
+            }
+            Pdf2KeyError::Cancelled => f.write_str("Conversão cancelada"),
+        }
+    }
+}
+
+impl std::error::Error for Pdf2KeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Pdf2KeyError::InvalidPageSelection(e) => Some(e),
+            Pdf2KeyError::ImageEncode { source, .. } => Some(source),
+            Pdf2KeyError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<PageSelectionError> for Pdf2KeyError {
+    fn from(e: PageSelectionError) -> Self {
+        Pdf2KeyError::InvalidPageSelection(e)
+    }
+}
@@ -0,0 +1,828 @@
+//! Interface gráfica (egui) do PDF2Key
+
+use eframe::egui;
+use pdf2key::keynote::KeynoteBackend;
+use pdf2key::{
+    BeamerNotes, CancellationToken, ConversionRequest, FitMode, ImageEncoding, NotesSource, OutputFormat, OverlayMode, PageSelection,
+    Handout, Password, Pdf2KeyError, PdfInput, ProgressEvent, Resolution, SlideLayout, Trim,
+};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::process::Command;
+
+/// Limite comum de anexos de e-mail, para a opção "Limitar a 25 MB"
+const EMAIL_MAX_SIZE: u64 = 25_000_000;
+
+// Paleta de cores premium (Dark Theme First)
+#[allow(dead_code)]
+struct AppColors;
+
+#[allow(dead_code)]
+impl AppColors {
+    // Fundo Principal (Deep Blue/Black)
+    const BG_MAIN: egui::Color32 = egui::Color32::from_rgb(13, 17, 23); // GitHub Dark Dimmed style
+    
+    // Cores primárias (Electric Blue)
+    const PRIMARY: egui::Color32 = egui::Color32::from_rgb(56, 189, 248); // Light Blue 400
+    const PRIMARY_HOVER: egui::Color32 = egui::Color32::from_rgb(14, 165, 233); // Sky 500
+    const PRIMARY_ACTIVE: egui::Color32 = egui::Color32::from_rgb(2, 132, 199); // Sky 600
+    
+    // Sucesso (Neon Green)
+    const SUCCESS: egui::Color32 = egui::Color32::from_rgb(74, 222, 128); // Green 400
+    const SUCCESS_BG: egui::Color32 = egui::Color32::from_rgb(20, 83, 45); // Green 900
+    
+    // Erro (Soft Red)
+    const ERROR: egui::Color32 = egui::Color32::from_rgb(248, 113, 113);
+    const ERROR_BG: egui::Color32 = egui::Color32::from_rgb(69, 10, 10);
+    
+    // Neutros
+    const TEXT_PRIMARY: egui::Color32 = egui::Color32::from_rgb(241, 245, 249); // Slate 100
+    const TEXT_SECONDARY: egui::Color32 = egui::Color32::from_rgb(148, 163, 184); // Slate 400
+    
+    const CARD_BG: egui::Color32 = egui::Color32::from_rgb(30, 41, 59); // Slate 800
+    const CARD_BORDER: egui::Color32 = egui::Color32::from_rgb(51, 65, 85); // Slate 700
+    const CARD_BORDER_HOVER: egui::Color32 = egui::Color32::from_rgb(71, 85, 105); // Slate 600
+    
+    const PROGRESS_BG: egui::Color32 = egui::Color32::from_rgb(51, 65, 85);
+}
+
+/// Abre a janela principal do PDF2Key
+pub fn run() -> eframe::Result<()> {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([600.0, 520.0])
+            .with_min_inner_size([550.0, 480.0])
+            .with_title("PDF2Key")
+            .with_resizable(true),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "PDF2Key",
+        options,
+        Box::new(|cc| {
+            let mut style = (*cc.egui_ctx.style()).clone();
+            
+            // Forçar Dark Mode
+            style.visuals = egui::Visuals::dark();
+            style.visuals.window_fill = AppColors::BG_MAIN;
+            style.visuals.panel_fill = AppColors::BG_MAIN;
+            
+            // Espaçamento e Layout
+            style.spacing.item_spacing = egui::vec2(16.0, 16.0);
+            style.spacing.button_padding = egui::vec2(24.0, 16.0);
+            
+            // Cores Globais
+            style.visuals.widgets.noninteractive.fg_stroke.color = AppColors::TEXT_PRIMARY;
+            style.visuals.hyperlink_color = AppColors::PRIMARY;
+            
+            // Fontes
+            style.text_styles.insert(
+                egui::TextStyle::Heading,
+                egui::FontId::new(32.0, egui::FontFamily::Proportional),
+            );
+            style.text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(16.0, egui::FontFamily::Proportional),
+            );
+            style.text_styles.insert(
+                egui::TextStyle::Button,
+                egui::FontId::new(16.0, egui::FontFamily::Proportional),
+            );
+            
+            cc.egui_ctx.set_style(style);
+            
+            Ok(Box::new(Pdf2KeyApp::default()))
+        }),
+    )
+}
+
+#[derive(Default)]
+struct Pdf2KeyApp {
+    /// PDFs de entrada, na ordem dos slides
+    inputs: Vec<InputEntry>,
+    /// Uma seção por PDF de entrada
+    file_sections: bool,
+    output_path: Option<PathBuf>,
+    output_format: OutputFormat,
+    keynote_backend: KeynoteBackend,
+    /// Expressão de seleção de páginas (vazia = todas)
+    page_expression: String,
+    /// Origem das notas do apresentador
+    notes: NotesSource,
+    /// Páginas do Beamer com as notas ao lado do slide
+    beamer_notes: bool,
+    /// Divide páginas de apostila (vários slides por página) em slides
+    handout: bool,
+    /// Tratamento das páginas de overlay do Beamer
+    overlays: OverlayMode,
+    /// Textos e imagens do PDF como objetos editáveis
+    editable: bool,
+    /// Fotos do PDF na resolução original sobre o fundo
+    native_images: bool,
+    /// Slides em SVG vetorial, com a imagem como alternativa
+    vector: bool,
+    /// Tamanho das imagens das páginas
+    resolution: Resolution,
+    /// Encaixe das páginas nos slides e cor das faixas
+    layout: SlideLayout,
+    /// Corta as margens brancas das páginas
+    trim: bool,
+    /// Mesmo corte em todas as páginas
+    trim_uniform: bool,
+    /// Formato das imagens dos slides
+    encoding: ImageEncoding,
+    /// Limita as imagens ao tamanho de um anexo de e-mail
+    email_size: bool,
+    status: Arc<Mutex<AppStatus>>,
+    is_converting: Arc<Mutex<bool>>,
+    /// Cancela a conversão em andamento
+    cancellation: CancellationToken,
+}
+
+/// PDF da lista de entrada
+struct InputEntry {
+    path: PathBuf,
+    /// Expressão de seleção de páginas deste PDF (vazia = a seleção geral)
+    pages: String,
+    /// Senha deste PDF, pedida só se ele for protegido (mantida só em memória)
+    password: String,
+}
+
+impl InputEntry {
+    fn new(path: PathBuf) -> Self {
+        Self { path, pages: String::new(), password: String::new() }
+    }
+}
+
+#[derive(Default, Clone)]
+struct AppStatus {
+    message: String,
+    progress: f32,
+    is_error: bool,
+    is_success: bool,
+    /// PDF que pediu senha: mostra o campo para digitá-la
+    needs_password: Option<PathBuf>,
+}
+
+impl eframe::App for Pdf2KeyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if *self.is_converting.lock().unwrap() {
+            ctx.request_repaint();
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Removemos o scroll e ajustamos as margens para um fit perfeito
+            egui::Frame::none()
+                .fill(ui.visuals().window_fill()) 
+                .inner_margin(20.0) // Reduzi um pouco a margem externa
+                .show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.set_max_width(500.0);
+                        ui.add_space(10.0); // Espaço menor no topo
+                        
+                        // Header
+                        ui.label(
+                            egui::RichText::new("📄 PDF2Key")
+                                .size(36.0) // Leve redução
+                                .color(AppColors::PRIMARY)
+                                .strong()
+                        );
+                        ui.add_space(6.0);
+                        ui.label(
+                            egui::RichText::new("Transforme seus PDFs em Keynote rapidamente.")
+                                .color(AppColors::TEXT_SECONDARY)
+                        );
+                        
+                        ui.add_space(24.0); // Reduzi de 40.0 para 24.0
+                        
+                        // Estados
+                        let is_converting = *self.is_converting.lock().unwrap();
+                        let status = self.status.lock().unwrap().clone();
+                        let has_file = !self.inputs.is_empty();
+                        
+                        // --- CARD PRINCIPAL ---
+                        let card_color = if is_converting {
+                             AppColors::CARD_BG
+                        } else if status.is_success {
+                             AppColors::SUCCESS_BG
+                        } else {
+                             AppColors::CARD_BG
+                        };
+                        
+                        let border_color = if is_converting {
+                            AppColors::PRIMARY
+                        } else if status.is_success {
+                            AppColors::SUCCESS
+                        } else if has_file {
+                            AppColors::PRIMARY
+                        } else {
+                            AppColors::CARD_BORDER
+                        };
+
+                        let card_response = egui::Frame::group(ui.style())
+                            .inner_margin(30.0) // Reduzi de 40.0 para 30.0
+                            .rounding(16.0)
+                            .stroke(egui::Stroke::new(2.0, border_color))
+                            .fill(card_color)
+                            .show(ui, |ui| {
+                                ui.set_min_width(ui.available_width());
+                                ui.set_min_height(140.0);
+                                
+                                ui.vertical_centered(|ui| {
+                                    if is_converting {
+                                        ui.spinner();
+                                        ui.add_space(16.0);
+                                        
+                                        // Custom Progress Bar Dark
+                                        let w = ui.available_width();
+                                        let h = 8.0;
+                                        let (rect, _) = ui.allocate_exact_size(egui::vec2(w, h), egui::Sense::hover());
+                                        
+                                        ui.painter().rect_filled(rect, 4.0, AppColors::PROGRESS_BG);
+                                        if status.progress > 0.0 {
+                                            let fill_w = w * status.progress;
+                                            let fill_rect = egui::Rect::from_min_size(rect.min, egui::vec2(fill_w, h));
+                                            ui.painter().rect_filled(fill_rect, 4.0, AppColors::PRIMARY);
+                                        }
+                                        
+                                        ui.add_space(12.0);
+                                        ui.label(egui::RichText::new(&status.message).color(AppColors::TEXT_SECONDARY));
+                                        
+                                    } else if status.is_success {
+                                        ui.label(egui::RichText::new("🚀 Sucesso!").size(24.0).strong().color(AppColors::SUCCESS));
+                                        ui.add_space(8.0);
+                                        if let Some(path) = &self.output_path {
+                                            ui.label(
+                                                egui::RichText::new(path.file_name().unwrap_or_default().to_string_lossy())
+                                                    .monospace()
+                                                    .color(AppColors::TEXT_PRIMARY)
+                                            );
+                                        }
+                                    } else if let Some(first) = self.inputs.first() {
+                                        ui.label(egui::RichText::new("📄 Arquivo Pronto").size(20.0).strong().color(AppColors::PRIMARY));
+                                        ui.add_space(8.0);
+                                        let name = match self.inputs.len() {
+                                            1 => first.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                                            count => format!("{} PDFs numa apresentação", count),
+                                        };
+                                        ui.label(
+                                            egui::RichText::new(name)
+                                                .size(16.0)
+                                                .color(AppColors::TEXT_PRIMARY)
+                                        );
+                                        ui.add_space(12.0);
+                                        ui.label(egui::RichText::new("Clique para alterar").size(12.0).color(AppColors::TEXT_SECONDARY));
+                                    } else {
+                                        ui.label(egui::RichText::new("📂").size(48.0).color(AppColors::TEXT_SECONDARY));
+                                        ui.add_space(16.0);
+                                        ui.label(egui::RichText::new("Clique para selecionar um PDF").size(18.0).strong().color(AppColors::TEXT_PRIMARY));
+                                    }
+                                });
+                            }).response;
+
+                        if !is_converting && !status.is_success {
+                            if card_response.hovered() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                            }
+                            if card_response.interact(egui::Sense::click()).clicked() {
+                                self.select_pdf();
+                            }
+                        }
+
+                        if !is_converting && !status.is_error && !status.is_success && !status.message.is_empty() {
+                            ui.add_space(16.0);
+                            ui.label(egui::RichText::new(&status.message).color(AppColors::TEXT_SECONDARY));
+                        }
+
+                        if status.is_error {
+                            ui.add_space(16.0);
+                            egui::Frame::none()
+                                .fill(AppColors::ERROR_BG)
+                                .inner_margin(12.0)
+                                .rounding(8.0)
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new(format!("Erro: {}", status.message)).color(AppColors::ERROR));
+                                });
+                        }
+
+                        ui.add_space(32.0); // Reduzi margem bottom
+
+                        // --- ACTIONS ---
+                        if !is_converting {
+                            if status.is_success {
+                                ui.horizontal(|ui| {
+                                    ui.columns(2, |cols| {
+                                        cols[0].vertical_centered_justified(|ui| {
+                                            let btn = egui::Button::new(
+                                                egui::RichText::new("📂 Abrir Pasta").strong().color(egui::Color32::BLACK)
+                                            )
+                                            .min_size(egui::vec2(0.0, 50.0))
+                                            .fill(AppColors::PRIMARY)
+                                            .rounding(10.0);
+                                            
+                                            if ui.add(btn).clicked() {
+                                                if let Some(path) = &self.output_path {
+                                                     let _ = Command::new("open").arg("-R").arg(path).spawn();
+                                                }
+                                            }
+                                        });
+                                        
+                                        cols[1].vertical_centered_justified(|ui| {
+                                            let btn = egui::Button::new(
+                                                egui::RichText::new("🔄 Novo").strong().color(AppColors::TEXT_PRIMARY)
+                                            )
+                                            .min_size(egui::vec2(0.0, 50.0))
+                                            .fill(egui::Color32::TRANSPARENT)
+                                            .stroke(egui::Stroke::new(1.0, AppColors::CARD_BORDER))
+                                            .rounding(10.0);
+                                            
+                                            if ui.add(btn).clicked() {
+                                                self.inputs.clear();
+                                                self.output_path = None;
+                                                let mut s = self.status.lock().unwrap();
+                                                s.is_success = false;
+                                                s.message = String::new();
+                                            }
+                                        });
+                                    });
+                                });
+                            } else {
+                                if has_file {
+                                    // Vários PDFs: ordem, páginas de cada um e seções
+                                    if self.inputs.len() > 1 {
+                                        let count = self.inputs.len();
+                                        let mut swap = None;
+                                        let mut remove = None;
+                                        for (i, input) in self.inputs.iter_mut().enumerate() {
+                                            ui.horizontal(|ui| {
+                                                if ui.add_enabled(i > 0, egui::Button::new("↑").small()).clicked() {
+                                                    swap = Some((i - 1, i));
+                                                }
+                                                if ui.add_enabled(i + 1 < count, egui::Button::new("↓").small()).clicked() {
+                                                    swap = Some((i, i + 1));
+                                                }
+                                                if ui.small_button("✕").clicked() {
+                                                    remove = Some(i);
+                                                }
+                                                ui.add(
+                                                    egui::TextEdit::singleline(&mut input.pages)
+                                                        .hint_text("páginas")
+                                                        .desired_width(90.0),
+                                                );
+                                                ui.label(
+                                                    egui::RichText::new(input.path.file_name().unwrap_or_default().to_string_lossy())
+                                                        .color(AppColors::TEXT_PRIMARY),
+                                                );
+                                            });
+                                        }
+                                        if let Some((a, b)) = swap {
+                                            self.inputs.swap(a, b);
+                                        }
+                                        if let Some(i) = remove {
+                                            self.inputs.remove(i);
+                                        }
+                                        ui.checkbox(
+                                            &mut self.file_sections,
+                                            egui::RichText::new("Uma seção por arquivo").color(AppColors::TEXT_SECONDARY),
+                                        );
+                                    }
+                                    if ui.small_button("+ Adicionar PDF").clicked() {
+                                        self.add_pdfs();
+                                    }
+
+                                    ui.horizontal(|ui| {
+                                        for format in OutputFormat::ALL {
+                                            let text = egui::RichText::new(format.label()).color(
+                                                if self.output_format == format { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            ui.selectable_value(&mut self.output_format, format, text);
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Páginas").color(AppColors::TEXT_SECONDARY));
+                                        ui.add(
+                                            egui::TextEdit::singleline(&mut self.page_expression)
+                                                .hint_text("todas (ex: 1-5,8,12-, odd, last 3)")
+                                                .desired_width(f32::INFINITY),
+                                        );
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Notas").color(AppColors::TEXT_SECONDARY));
+                                        for (source, label) in [(NotesSource::None, "Nenhuma"), (NotesSource::PageText, "Texto da página")] {
+                                            let text = egui::RichText::new(label).color(
+                                                if self.notes == source { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            ui.selectable_value(&mut self.notes, source, text);
+                                        }
+                                        let sidecar_label = match &self.notes {
+                                            NotesSource::Sidecar(path) => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                                            _ => "Arquivo...".to_string(),
+                                        };
+                                        let is_sidecar = matches!(self.notes, NotesSource::Sidecar(_));
+                                        let text = egui::RichText::new(sidecar_label).color(
+                                            if is_sidecar { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                        );
+                                        if ui.selectable_label(is_sidecar, text).clicked() {
+                                            if let Some(path) = rfd::FileDialog::new()
+                                                .add_filter("Texto", &["txt", "md"])
+                                                .pick_file()
+                                            {
+                                                self.notes = NotesSource::Sidecar(path);
+                                            }
+                                        }
+                                    });
+
+                                    if ui.checkbox(
+                                        &mut self.beamer_notes,
+                                        egui::RichText::new("Beamer com notas ao lado (usa o texto como notas)").color(AppColors::TEXT_SECONDARY),
+                                    ).changed() && self.beamer_notes && self.notes == NotesSource::None {
+                                        self.notes = NotesSource::PageText;
+                                    }
+
+                                    ui.checkbox(
+                                        &mut self.handout,
+                                        egui::RichText::new("Apostila (vários slides por página)").color(AppColors::TEXT_SECONDARY),
+                                    );
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Overlays").color(AppColors::TEXT_SECONDARY));
+                                        for (mode, label) in [
+                                            (OverlayMode::Off, "Uma página por slide"),
+                                            (OverlayMode::LastFrame, "Última página"),
+                                            (OverlayMode::Builds, "Builds"),
+                                        ] {
+                                            let text = egui::RichText::new(label).color(
+                                                if self.overlays == mode { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            ui.selectable_value(&mut self.overlays, mode, text);
+                                        }
+                                    });
+
+                                    // O Keynote nativo não grava objetos
+                                    let objects = !self.keynote_native();
+                                    ui.add_enabled(
+                                        objects,
+                                        egui::Checkbox::new(
+                                            &mut self.editable,
+                                            egui::RichText::new("Texto editável (caixas de texto sobre o fundo)").color(AppColors::TEXT_SECONDARY),
+                                        ),
+                                    );
+
+                                    // O modo editável já extrai as imagens
+                                    ui.add_enabled(
+                                        objects && !self.editable,
+                                        egui::Checkbox::new(
+                                            &mut self.native_images,
+                                            egui::RichText::new("Fotos na resolução original").color(AppColors::TEXT_SECONDARY),
+                                        ),
+                                    );
+
+                                    ui.checkbox(
+                                        &mut self.vector,
+                                        egui::RichText::new("Slides vetoriais (SVG)").color(AppColors::TEXT_SECONDARY),
+                                    );
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Resolução").color(AppColors::TEXT_SECONDARY));
+                                        for (resolution, label) in [
+                                            (Resolution::FULL_HD, "1920×1080"),
+                                            (Resolution::keynote_slide(2), "3840×2160"),
+                                            (Resolution::Dpi(300), "300 DPI"),
+                                        ] {
+                                            let text = egui::RichText::new(label).color(
+                                                if self.resolution == resolution { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            ui.selectable_value(&mut self.resolution, resolution, text);
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.checkbox(
+                                            &mut self.trim,
+                                            egui::RichText::new("Cortar margens").color(AppColors::TEXT_SECONDARY),
+                                        );
+                                        ui.add_enabled(
+                                            self.trim,
+                                            egui::Checkbox::new(
+                                                &mut self.trim_uniform,
+                                                egui::RichText::new("Mesmo corte em todas").color(AppColors::TEXT_SECONDARY),
+                                            ),
+                                        );
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Encaixe").color(AppColors::TEXT_SECONDARY));
+                                        for (fit, label) in [
+                                            (FitMode::MatchPage, "Como o PDF"),
+                                            (FitMode::Letterbox, "Faixas"),
+                                            (FitMode::Crop, "Cortar"),
+                                            (FitMode::Stretch, "Esticar"),
+                                        ] {
+                                            let text = egui::RichText::new(label).color(
+                                                if self.layout.fit == fit { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            ui.selectable_value(&mut self.layout.fit, fit, text);
+                                        }
+                                        // Faixas aparecem no modo como o PDF quando as páginas mudam de proporção
+                                        if matches!(self.layout.fit, FitMode::MatchPage | FitMode::Letterbox) {
+                                            ui.color_edit_button_srgb(&mut self.layout.background)
+                                                .on_hover_text("Cor das faixas");
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new("Imagens").color(AppColors::TEXT_SECONDARY));
+                                        let quality = self.encoding.quality();
+                                        for (encoding, label) in [
+                                            (ImageEncoding::Png, "PNG"),
+                                            (ImageEncoding::Jpeg { quality }, "JPEG"),
+                                            (ImageEncoding::WebP { quality: None }, "WebP"),
+                                            (ImageEncoding::Auto { quality }, "Automático"),
+                                        ] {
+                                            // A qualidade escolhida é mantida ao trocar entre JPEG e automático
+                                            let selected = std::mem::discriminant(&self.encoding) == std::mem::discriminant(&encoding);
+                                            let text = egui::RichText::new(label).color(
+                                                if selected { AppColors::PRIMARY } else { AppColors::TEXT_SECONDARY }
+                                            );
+                                            if ui.selectable_label(selected, text).clicked() {
+                                                self.encoding = encoding;
+                                            }
+                                        }
+                                    });
+
+                                    if let ImageEncoding::Jpeg { quality } | ImageEncoding::Auto { quality } = &mut self.encoding {
+                                        ui.add(egui::Slider::new(quality, 10..=100).text("Qualidade JPEG"));
+                                    }
+
+                                    ui.checkbox(
+                                        &mut self.email_size,
+                                        egui::RichText::new("Limitar a 25 MB (anexo de e-mail)").color(AppColors::TEXT_SECONDARY),
+                                    );
+
+                                    let locked = status.needs_password.as_ref()
+                                        .and_then(|path| self.inputs.iter_mut().find(|entry| &entry.path == path));
+                                    if let Some(entry) = locked {
+                                        ui.horizontal(|ui| {
+                                            let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+                                            ui.label(egui::RichText::new(format!("Senha de {}", name)).color(AppColors::TEXT_SECONDARY));
+                                            ui.add(
+                                                egui::TextEdit::singleline(&mut entry.password)
+                                                    .password(true)
+                                                    .hint_text("senha do PDF")
+                                                    .desired_width(f32::INFINITY),
+                                            );
+                                        });
+                                    }
+                                }
+
+                                // No macOS é possível escolher entre o Keynote.app e o escritor nativo
+                                if cfg!(target_os = "macos") && has_file && self.output_format == OutputFormat::Keynote {
+                                    let mut native = self.keynote_backend == KeynoteBackend::Native;
+                                    if ui.checkbox(
+                                        &mut native,
+                                        egui::RichText::new("Gerar sem abrir o Keynote").color(AppColors::TEXT_SECONDARY),
+                                    ).changed() {
+                                        self.keynote_backend = if native { KeynoteBackend::Native } else { KeynoteBackend::AppleScript };
+                                    }
+                                }
+
+                                let btn_text = if !has_file {
+                                    "Selecione um arquivo"
+                                } else if status.needs_password.is_some() {
+                                    "Desbloquear e converter"
+                                } else {
+                                    "Converter agora"
+                                };
+                                let btn_color = if has_file { AppColors::PRIMARY } else { AppColors::CARD_BORDER };
+                                let txt_color = if has_file { egui::Color32::BLACK } else { AppColors::TEXT_SECONDARY };
+                                
+                                let btn = egui::Button::new(
+                                    egui::RichText::new(btn_text).size(18.0).strong().color(txt_color)
+                                )
+                                .min_size(egui::vec2(ui.available_width(), 56.0))
+                                .fill(btn_color)
+                                .rounding(12.0);
+                                
+                                if ui.add_enabled(has_file, btn).clicked() {
+                                    if let Some(first) = self.inputs.first() {
+                                        let mut output = first.path.clone();
+                                        output.set_extension(self.output_format.extension());
+                                        self.output_path = Some(output);
+                                    }
+                                    self.start_conversion(ctx.clone());
+                                }
+                            }
+                        } else {
+                            let cancelling = self.cancellation.is_cancelled();
+                            let btn = egui::Button::new(
+                                egui::RichText::new("Cancelar").strong().color(AppColors::TEXT_PRIMARY)
+                            )
+                            .min_size(egui::vec2(ui.available_width(), 50.0))
+                            .fill(egui::Color32::TRANSPARENT)
+                            .stroke(egui::Stroke::new(1.0, AppColors::CARD_BORDER))
+                            .rounding(10.0);
+
+                            if ui.add_enabled(!cancelling, btn).clicked() {
+                                self.cancellation.cancel();
+                                self.status.lock().unwrap().message = ProgressEvent::Cancelling.message();
+                            }
+                        }
+                    });
+                });
+        });
+    }
+}
+
+impl Pdf2KeyApp {
+    /// Saída em Keynote pelo escritor nativo, que não grava objetos
+    fn keynote_native(&self) -> bool {
+        self.output_format == OutputFormat::Keynote && self.keynote_backend == KeynoteBackend::Native
+    }
+
+    fn select_pdf(&mut self) {
+        if let Some(paths) = rfd::FileDialog::new()
+            .add_filter("PDF", &["pdf"])
+            .pick_files()
+        {
+            self.inputs = paths.into_iter().map(InputEntry::new).collect();
+            // Reseta status
+            let mut status = self.status.lock().unwrap();
+            status.message = String::new();
+            status.is_error = false;
+            status.is_success = false;
+            status.needs_password = None;
+            status.progress = 0.0;
+        }
+    }
+
+    /// Acrescenta PDFs ao fim da lista
+    fn add_pdfs(&mut self) {
+        if let Some(paths) = rfd::FileDialog::new()
+            .add_filter("PDF", &["pdf"])
+            .pick_files()
+        {
+            self.inputs.extend(paths.into_iter().map(InputEntry::new));
+        }
+    }
+
+    fn start_conversion(&mut self, ctx: egui::Context) {
+        // Valida a seleção de páginas antes de iniciar
+        let pages = match PageSelection::parse(&self.page_expression) {
+            Ok(pages) => pages,
+            Err(e) => {
+                let mut s = self.status.lock().unwrap();
+                s.message = format!("Seleção de páginas inválida: {}", e);
+                s.is_error = true;
+                s.is_success = false;
+                return;
+            }
+        };
+
+        let mut inputs = Vec::with_capacity(self.inputs.len());
+        for entry in &self.inputs {
+            let mut input = PdfInput::new(&entry.path);
+            if !entry.password.is_empty() {
+                input = input.with_password(Password::new(entry.password.as_str()));
+            }
+            if !entry.pages.trim().is_empty() {
+                match PageSelection::parse(&entry.pages) {
+                    Ok(pages) => input = input.with_pages(pages),
+                    Err(e) => {
+                        let mut s = self.status.lock().unwrap();
+                        let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+                        s.message = format!("Seleção de páginas inválida em {}: {}", name, e);
+                        s.is_error = true;
+                        s.is_success = false;
+                        return;
+                    }
+                }
+            }
+            inputs.push(input);
+        }
+        let file_sections = self.file_sections && inputs.len() > 1;
+        let output_path = self.output_path.clone().unwrap();
+        let output_format = self.output_format;
+        let keynote_backend = self.keynote_backend;
+        let notes = self.notes.clone();
+        let beamer_notes = if self.beamer_notes { BeamerNotes::Auto } else { BeamerNotes::Off };
+        let handout = self.handout.then(Handout::auto);
+        let overlays = self.overlays;
+        let editable = self.editable && !self.keynote_native();
+        let native_images = self.native_images && !self.keynote_native();
+        let vector = self.vector;
+        let resolution = self.resolution;
+        let layout = self.layout;
+        let trim = self.trim.then(|| Trim::default().with_uniform(self.trim_uniform));
+        let encoding = self.encoding;
+        let max_size = self.email_size.then_some(EMAIL_MAX_SIZE);
+        let status = Arc::clone(&self.status);
+        let is_converting = Arc::clone(&self.is_converting);
+        self.cancellation = CancellationToken::new();
+        let cancellation = self.cancellation.clone();
+        
+        *is_converting.lock().unwrap() = true;
+        
+        {
+            let mut s = status.lock().unwrap();
+            s.is_error = false;
+            s.is_success = false;
+            s.message = "Inicializando...".to_string();
+            s.progress = 0.0;
+        }
+        
+        thread::spawn(move || {
+            // Páginas que ficaram só na imagem no modo vetorial, listadas ao concluir
+            let rasterized = Mutex::new(Vec::new());
+            // Partes do pedido que a conversão não atendeu, também listadas ao concluir
+            let warnings = Mutex::new(Vec::new());
+            let report = |event: &ProgressEvent| {
+                match event {
+                    ProgressEvent::RasterFallback { page, .. } => rasterized.lock().unwrap().push(page.to_string()),
+                    ProgressEvent::Warning(_) => warnings.lock().unwrap().push(event.message()),
+                    _ => {}
+                }
+                {
+                    let mut s = status.lock().unwrap();
+                    // Depois do pedido de cancelamento mantém a mensagem "Cancelando..."
+                    if !cancellation.is_cancelled() {
+                        s.message = event.message();
+                    }
+                    // Renderização e codificação correm em paralelo: a barra nunca volta
+                    s.progress = s.progress.max(event.fraction());
+                }
+                ctx.request_repaint();
+            };
+            let request = ConversionRequest::new(&inputs[0].path, &output_path)
+                .with_inputs(inputs)
+                .with_format(output_format)
+                .with_keynote_backend(keynote_backend)
+                .with_pages(pages)
+                .with_file_sections(file_sections)
+                .with_handout(handout)
+                .with_notes(notes)
+                .with_beamer_notes(beamer_notes)
+                .with_overlays(overlays)
+                .with_editable(editable)
+                .with_native_images(native_images)
+                .with_vector(vector)
+                .with_resolution(resolution)
+                .with_layout(layout)
+                .with_trim(trim)
+                .with_encoding(encoding)
+                .with_max_size(max_size)
+                .with_cancellation(cancellation.clone());
+            let result = pdf2key::convert(&request, &report);
+            
+            *is_converting.lock().unwrap() = false;
+            
+            let mut status_guard = status.lock().unwrap();
+            match result {
+                Ok(_) => {
+                    let rasterized = rasterized.lock().unwrap();
+                    let mut message = if rasterized.is_empty() {
+                        "Concluído!".to_string()
+                    } else {
+                        format!("Concluído! Páginas só em imagem: {}", rasterized.join(", "))
+                    };
+                    for warning in warnings.lock().unwrap().iter() {
+                        message.push_str(&format!("\nAviso: {}", warning));
+                    }
+                    status_guard.message = message;
+                    status_guard.progress = 1.0;
+                    status_guard.is_error = false;
+                    status_guard.is_success = true;
+                    status_guard.needs_password = None;
+                }
+                Err(Pdf2KeyError::Cancelled) => {
+                    status_guard.message = "Conversão cancelada".to_string();
+                    status_guard.progress = 0.0;
+                    status_guard.is_error = false;
+                    status_guard.is_success = false;
+                }
+                Err(e) => {
+                    status_guard.needs_password = match &e {
+                        Pdf2KeyError::PasswordRequired { path } | Pdf2KeyError::IncorrectPassword { path } => Some(path.clone()),
+                        _ => None,
+                    };
+                    status_guard.message = match &e {
+                        Pdf2KeyError::PdfiumNotFound { .. } => {
+                            format!("{}. Coloque a biblioteca na pasta lib/ ao lado do aplicativo.", e)
+                        }
+                        Pdf2KeyError::BackendFailed { format: OutputFormat::Keynote, .. } => {
+                            format!("{}. Verifique as permissões de automação do Keynote.", e)
+                        }
+                        _ => e.to_string(),
+                    };
+                    status_guard.is_error = true;
+                    // Se der erro, não mostra botão de sucesso
+                    status_guard.is_success = false; 
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+}
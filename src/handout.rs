@@ -0,0 +1,357 @@
+//! Páginas de apostila (vários slides por página) divididas em slides
+//! A grade vem da análise de uma renderização reduzida ou é informada; cada célula vira
+//! uma página à parte em [`crate::pdf_processor::PdfDocumentHandle`], antes do builder.
+
+use crate::conversion::CancellationToken;
+use crate::error::Pdf2KeyError;
+use crate::links::PixelRect;
+use crate::pdf_processor::PdfDocumentHandle;
+use crate::trim::{InkMap, PageRegion};
+use image::RgbaImage;
+
+/// Tamanho mínimo de uma faixa com conteúdo, em fração do lado da página, para contar
+/// como linha ou coluna da grade; cabeçalhos e números de página ficam de fora
+const MIN_BAND: f32 = 0.08;
+
+/// Diferença máxima entre os tamanhos das faixas de uma grade detectada (slides de
+/// apostila têm todos o mesmo tamanho)
+const BAND_SIZE_TOLERANCE: f32 = 0.25;
+
+/// Fração de tinta a partir da qual a borda do conteúdo é uma linha de moldura
+const FRAME_LINE_FILL: f32 = 0.85;
+
+/// Espessura máxima da moldura removida, em pixels da renderização reduzida
+const MAX_FRAME_WIDTH: u32 = 6;
+
+/// Ordem dos slides na página
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PageOrder {
+    /// Linha a linha, da esquerda para a direita (padrão)
+    #[default]
+    RowMajor,
+    /// Coluna a coluna, de cima para baixo
+    ColumnMajor,
+}
+
+impl PageOrder {
+    /// Interpreta `rows` ou `columns`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rows" | "row" => Some(PageOrder::RowMajor),
+            "columns" | "column" => Some(PageOrder::ColumnMajor),
+            _ => None,
+        }
+    }
+}
+
+/// Divisão de páginas de apostila
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handout {
+    /// Colunas e linhas; `None` detecta pelas faixas em branco entre os slides
+    pub grid: Option<(u32, u32)>,
+    pub order: PageOrder,
+    /// Recorta cada célula no seu conteúdo e remove as molduras em volta dos slides
+    pub borders: bool,
+}
+
+impl Default for Handout {
+    fn default() -> Self {
+        Self { grid: None, order: PageOrder::default(), borders: true }
+    }
+}
+
+impl Handout {
+    /// Grade detectada em cada página
+    pub fn auto() -> Self {
+        Self::default()
+    }
+
+    /// Grade fixa de `columns` × `rows` células iguais
+    pub fn grid(columns: u32, rows: u32) -> Self {
+        Self { grid: Some((columns.max(1), rows.max(1))), ..Self::default() }
+    }
+
+    pub fn with_order(mut self, order: PageOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_borders(mut self, borders: bool) -> Self {
+        self.borders = borders;
+        self
+    }
+
+    /// Interpreta `auto` ou `<colunas>x<linhas>`, ex: `2x3`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace('×', "x");
+        if name == "auto" {
+            return Some(Self::auto());
+        }
+        let (columns, rows) = name.split_once('x')?;
+        let (columns, rows): (u32, u32) = (columns.parse().ok()?, rows.parse().ok()?);
+        (columns > 0 && rows > 0).then_some(Self::grid(columns, rows))
+    }
+}
+
+/// Slide tirado de uma página do PDF: a página inteira ou uma célula da apostila
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageView {
+    /// Índice (0-based) da página no PDF
+    pub page: usize,
+    /// Região do slide na página; `None` para a página inteira
+    pub cell: Option<PageRegion>,
+}
+
+/// Slides das páginas `indices`, na ordem: as células de cada apostila em sequência
+///
+/// Páginas que não parecem apostilas (com a grade detectada) continuam inteiras.
+pub(crate) fn page_views(
+    document: &PdfDocumentHandle,
+    indices: &[usize],
+    handout: &Handout,
+    cancellation: &CancellationToken,
+) -> Result<Vec<PageView>, Pdf2KeyError> {
+    let mut views = Vec::with_capacity(indices.len());
+    let mut split = 0;
+    for &page in indices {
+        cancellation.check()?;
+        let cells = document.handout_cells(page, handout)?;
+        if cells.is_empty() {
+            views.push(PageView { page, cell: None });
+        } else {
+            split += 1;
+            views.extend(cells.into_iter().map(|cell| PageView { page, cell: Some(cell) }));
+        }
+    }
+    log::info!("{} de {} páginas divididas em {} slides", split, indices.len(), views.len());
+    Ok(views)
+}
+
+/// Células dos slides de uma página renderizada, na ordem de leitura
+///
+/// Vazio se a página não parece ter mais de um slide (só com a grade detectada).
+pub(crate) fn cells(image: &RgbaImage, handout: &Handout) -> Vec<PixelRect> {
+    let ink = InkMap::new(image);
+    let page = PixelRect { x: 0, y: 0, width: ink.width, height: ink.height };
+    let (columns, rows) = match handout.grid {
+        Some((columns, rows)) => (split_evenly(ink.width, columns), split_evenly(ink.height, rows)),
+        None => {
+            let columns = detect_bands(&ink.columns(&page), ink.width);
+            let rows = detect_bands(&ink.rows(&page), ink.height);
+            if columns.len() * rows.len() <= 1 {
+                return Vec::new();
+            }
+            (columns, rows)
+        }
+    };
+
+    let cell = |&(x, width): &(u32, u32), &(y, height): &(u32, u32)| {
+        let cell = PixelRect { x, y, width, height };
+        if !handout.borders {
+            return cell;
+        }
+        // Conteúdo da célula sem as faixas em branco em volta e sem a moldura
+        match ink.bounds(&cell) {
+            Some(content) => strip_frame(&ink, content),
+            None => cell,
+        }
+    };
+    match handout.order {
+        PageOrder::RowMajor => rows.iter().flat_map(|row| columns.iter().map(move |column| (column, row))).map(|(c, r)| cell(c, r)).collect(),
+        PageOrder::ColumnMajor => columns.iter().flat_map(|column| rows.iter().map(move |row| (column, row))).map(|(c, r)| cell(c, r)).collect(),
+    }
+}
+
+/// `count` faixas iguais de um lado de `size` pixels: (início, tamanho)
+fn split_evenly(size: u32, count: u32) -> Vec<(u32, u32)> {
+    (0..count)
+        .map(|i| {
+            let start = size * i / count;
+            (start, size * (i + 1) / count - start)
+        })
+        .collect()
+}
+
+/// Faixas com conteúdo separadas por linhas ou colunas em branco: (início, tamanho)
+///
+/// Faixas estreitas (cabeçalhos, rodapés) são descartadas; enquanto as faixas tiverem
+/// tamanhos muito diferentes, as separadas pelo menor espaço são unidas (um slide sem
+/// moldura pode ter espaços em branco por dentro).
+fn detect_bands(profile: &[u32], size: u32) -> Vec<(u32, u32)> {
+    let mut bands: Vec<(u32, u32)> = Vec::new();
+    let mut start = None;
+    for (i, &count) in profile.iter().chain(std::iter::once(&0)).enumerate() {
+        match (count > 0, start) {
+            (true, None) => start = Some(i as u32),
+            (false, Some(first)) => {
+                bands.push((first, i as u32 - first));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    let min_size = (size as f32 * MIN_BAND) as u32;
+    bands.retain(|&(_, length)| length >= min_size);
+
+    while bands.len() > 1 {
+        let largest = bands.iter().map(|&(_, length)| length).max().unwrap_or(0) as f32;
+        if bands.iter().all(|&(_, length)| length as f32 >= largest * (1.0 - BAND_SIZE_TOLERANCE)) {
+            break;
+        }
+        let narrowest_gap = (1..bands.len())
+            .min_by_key(|&i| bands[i].0 - (bands[i - 1].0 + bands[i - 1].1))
+            .unwrap_or(1);
+        let (first, _) = bands[narrowest_gap - 1];
+        let (next, length) = bands.remove(narrowest_gap);
+        bands[narrowest_gap - 1] = (first, next + length - first);
+    }
+    bands
+}
+
+/// Tira de `rect` as linhas de moldura nas bordas (linhas quase cheias de tinta)
+///
+/// Uma borda só é moldura se as linhas cheias acabam em até `MAX_FRAME_WIDTH` pixels:
+/// slides com fundo escuro são cheios de tinta e ficam como estão.
+fn strip_frame(ink: &InkMap, rect: PixelRect) -> PixelRect {
+    let (mut left, mut top) = (rect.x, rect.y);
+    let (mut right, mut bottom) = (rect.x + rect.width, rect.y + rect.height);
+    let frame_width = |lines: &mut dyn Iterator<Item = bool>| {
+        let width = lines.take(MAX_FRAME_WIDTH as usize + 1).take_while(|&line| line).count() as u32;
+        if width <= MAX_FRAME_WIDTH { width } else { 0 }
+    };
+    let is_line = |filled: usize, length: u32| filled as f32 >= length as f32 * FRAME_LINE_FILL;
+
+    let row = |y: u32| is_line((left..right).filter(|&x| ink.at(x, y)).count(), right - left);
+    let (top_width, bottom_width) = (frame_width(&mut (top..bottom).map(row)), frame_width(&mut (top..bottom).rev().map(row)));
+    if top_width + bottom_width < bottom - top {
+        top += top_width;
+        bottom -= bottom_width;
+    }
+
+    let column = |x: u32| is_line((top..bottom).filter(|&y| ink.at(x, y)).count(), bottom - top);
+    let (left_width, right_width) = (frame_width(&mut (left..right).map(column)), frame_width(&mut (left..right).rev().map(column)));
+    if left_width + right_width < right - left {
+        left += left_width;
+        right -= right_width;
+    }
+    PixelRect { x: left, y: top, width: right - left, height: bottom - top }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn fill(image: &mut RgbaImage, rect: PixelRect) {
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                image.put_pixel(x, y, BLACK);
+            }
+        }
+    }
+
+    /// Slide de `size` pixels em (x, y) com moldura de 1 pixel e um bloco no meio
+    fn framed_slide(image: &mut RgbaImage, x: u32, y: u32, size: u32) {
+        fill(image, PixelRect { x, y, width: size, height: 1 });
+        fill(image, PixelRect { x, y: y + size - 1, width: size, height: 1 });
+        fill(image, PixelRect { x, y, width: 1, height: size });
+        fill(image, PixelRect { x: x + size - 1, y, width: 1, height: size });
+        fill(image, PixelRect { x: x + size / 4, y: y + size / 4, width: size / 2, height: size / 2 });
+    }
+
+    /// Apostila de 200x300 com 2 colunas e 3 linhas de slides de 80 pixels
+    fn handout_page() -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(200, 300, Rgba([255, 255, 255, 255]));
+        for row in 0..3 {
+            for column in 0..2 {
+                framed_slide(&mut image, 10 + 100 * column, 10 + 100 * row, 80);
+            }
+        }
+        image
+    }
+
+    fn origins(cells: &[PixelRect]) -> Vec<(u32, u32)> {
+        cells.iter().map(|cell| (cell.x, cell.y)).collect()
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(PageOrder::from_name("Columns"), Some(PageOrder::ColumnMajor));
+        assert_eq!(PageOrder::from_name("row"), Some(PageOrder::RowMajor));
+        assert_eq!(PageOrder::from_name("diagonal"), None);
+        assert_eq!(Handout::from_name("auto"), Some(Handout::auto()));
+        assert_eq!(Handout::from_name("2×3"), Some(Handout::grid(2, 3)));
+        assert_eq!(Handout::from_name("0x3"), None);
+    }
+
+    #[test]
+    fn even_splits() {
+        assert_eq!(split_evenly(10, 3), [(0, 3), (3, 3), (6, 4)]);
+        assert_eq!(split_evenly(300, 1), [(0, 300)]);
+    }
+
+    #[test]
+    fn bands_drop_headers_and_join_gaps() {
+        let profile = |bands: &[(usize, usize)]| {
+            let mut profile = vec![0u32; 100];
+            for &(start, end) in bands {
+                profile[start..end].iter_mut().for_each(|count| *count = 5);
+            }
+            profile
+        };
+        assert_eq!(detect_bands(&profile(&[(10, 40), (55, 85)]), 100), [(10, 30), (55, 30)]);
+        // Cabeçalho estreito descartado
+        assert_eq!(detect_bands(&profile(&[(0, 3), (10, 40), (55, 85)]), 100), [(10, 30), (55, 30)]);
+        // Slide com um espaço em branco por dentro: as duas partes viram uma faixa
+        assert_eq!(detect_bands(&profile(&[(10, 40), (55, 70), (73, 85)]), 100), [(10, 30), (55, 30)]);
+        assert!(detect_bands(&profile(&[]), 100).is_empty());
+    }
+
+    #[test]
+    fn frames_are_stripped_but_dark_slides_stay() {
+        let mut image = RgbaImage::from_pixel(40, 30, Rgba([255, 255, 255, 255]));
+        fill(&mut image, PixelRect { x: 5, y: 5, width: 30, height: 2 });
+        fill(&mut image, PixelRect { x: 5, y: 23, width: 30, height: 2 });
+        fill(&mut image, PixelRect { x: 5, y: 5, width: 2, height: 20 });
+        fill(&mut image, PixelRect { x: 33, y: 5, width: 2, height: 20 });
+        fill(&mut image, PixelRect { x: 15, y: 12, width: 10, height: 6 });
+        let ink = InkMap::new(&image);
+        let content = ink.bounds(&PixelRect { x: 0, y: 0, width: 40, height: 30 }).unwrap();
+        assert_eq!(content, PixelRect { x: 5, y: 5, width: 30, height: 20 });
+        assert_eq!(strip_frame(&ink, content), PixelRect { x: 7, y: 7, width: 26, height: 16 });
+
+        fill(&mut image, PixelRect { x: 5, y: 5, width: 30, height: 20 });
+        let ink = InkMap::new(&image);
+        assert_eq!(strip_frame(&ink, content), content);
+    }
+
+    #[test]
+    fn detected_grid_in_both_orders() {
+        let page = handout_page();
+        let cells = cells(&page, &Handout::auto());
+        assert_eq!(cells.len(), 6);
+        // Sem a moldura de 1 pixel
+        assert_eq!(cells[0], PixelRect { x: 11, y: 11, width: 78, height: 78 });
+        assert_eq!(origins(&cells), [(11, 11), (111, 11), (11, 111), (111, 111), (11, 211), (111, 211)]);
+
+        let by_column = super::cells(&page, &Handout::auto().with_order(PageOrder::ColumnMajor));
+        assert_eq!(origins(&by_column), [(11, 11), (11, 111), (11, 211), (111, 11), (111, 111), (111, 211)]);
+
+        let unframed = super::cells(&page, &Handout::auto().with_borders(false));
+        assert_eq!(unframed[1], PixelRect { x: 110, y: 10, width: 80, height: 80 });
+    }
+
+    #[test]
+    fn fixed_grid_and_single_slide() {
+        let page = handout_page();
+        let cells = cells(&page, &Handout::grid(2, 3).with_borders(false));
+        assert_eq!(origins(&cells), [(0, 0), (100, 0), (0, 100), (100, 100), (0, 200), (100, 200)]);
+        assert_eq!(cells[5], PixelRect { x: 100, y: 200, width: 100, height: 100 });
+
+        let mut single = RgbaImage::from_pixel(200, 150, Rgba([255, 255, 255, 255]));
+        framed_slide(&mut single, 20, 10, 120);
+        assert!(super::cells(&single, &Handout::auto()).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+//! Imagens embutidas no PDF (fotos, figuras) na resolução original
+//! Evita reamostrar as fotos junto com a página: o fundo é renderizado sem elas e a
+//! imagem nativa é posicionada por cima. Também usado pelo comando `images`.
+
+use crate::error::Pdf2KeyError;
+use crate::links::PixelRect;
+use crate::page_selection::PageSelection;
+use crate::pdf_processor::{Password, PdfProcessor, Resolution};
+use image::{DynamicImage, ImageFormat, ImageResult};
+use std::path::{Path, PathBuf};
+
+/// Imagem de uma página com os pixels originais e a posição em que é desenhada
+pub struct EmbeddedImage {
+    /// Área ocupada pela imagem, em pixels da página renderizada
+    pub rect: PixelRect,
+    pub data: ImageData,
+}
+
+/// Conteúdo da imagem: o JPEG do PDF sem recompressão ou os pixels decodificados
+pub enum ImageData {
+    /// Bytes do fluxo DCTDecode, gravados como estão
+    Jpeg { bytes: Vec<u8>, width: u32, height: u32 },
+    /// Pixels com máscara e transformações aplicadas, gravados como PNG
+    Decoded(DynamicImage),
+}
+
+impl EmbeddedImage {
+    /// Tamanho nativo em pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        match &self.data {
+            ImageData::Jpeg { width, height, .. } => (*width, *height),
+            ImageData::Decoded(image) => (image.width(), image.height()),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self.data {
+            ImageData::Jpeg { .. } => "jpg",
+            ImageData::Decoded(_) => "png",
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> ImageResult<()> {
+        match &self.data {
+            ImageData::Jpeg { bytes, .. } => std::fs::write(path, bytes).map_err(image::ImageError::IoError),
+            ImageData::Decoded(image) => image.save_with_format(path, ImageFormat::Png),
+        }
+    }
+}
+
+/// Imagem gravada pelo comando `images`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpedImage {
+    /// Página de origem (1-based)
+    pub page: usize,
+    pub path: PathBuf,
+    /// Tamanho nativo em pixels
+    pub width: u32,
+    pub height: u32,
+    /// Posição na página, em pontos a partir do canto superior esquerdo
+    pub placement: PixelRect,
+}
+
+/// Grava as imagens embutidas nas páginas selecionadas em `output_dir`
+///
+/// Os arquivos se chamam `page{NNN}_img{KK}.jpg|png`, na ordem em que aparecem em cada página.
+pub fn dump(
+    input: &Path,
+    password: Option<&Password>,
+    pages: &PageSelection,
+    output_dir: &Path,
+) -> Result<Vec<DumpedImage>, Pdf2KeyError> {
+    let processor = PdfProcessor::new()?;
+    let document = processor.open_with_password(input, password)?;
+    std::fs::create_dir_all(output_dir)
+        .map_err(|source| Pdf2KeyError::Io { path: output_dir.to_path_buf(), source })?;
+
+    let mut dumped = Vec::new();
+    for index in document.select_pages(pages)? {
+        // A 72 DPI os pixels da página são pontos do PDF
+        for (k, image) in document.page_images(index, Resolution::Dpi(72))?.into_iter().enumerate() {
+            let path = output_dir.join(format!("page{:03}_img{:02}.{}", index + 1, k + 1, image.extension()));
+            image.save(&path).map_err(|source| Pdf2KeyError::ImageEncode { page: index + 1, source })?;
+            let (width, height) = image.dimensions();
+            dumped.push(DumpedImage { page: index + 1, path, width, height, placement: image.rect });
+        }
+    }
+    log::info!("{} imagens exportadas para {}", dumped.len(), output_dir.display());
+    Ok(dumped)
+}
@@ -0,0 +1,433 @@
+//! Primitivas do formato IWA (iWork Archive)
+//! Protobuf mínimo + container snappy usado em Index/*.iwa dos pacotes .key
+
+/// Tamanho máximo (descomprimido) de cada bloco snappy de um arquivo .iwa
+const IWA_CHUNK_SIZE: usize = 0x10000;
+
+/// Escritor protobuf mínimo (wire format), no estilo builder
+#[derive(Default, Clone)]
+pub struct ProtoWriter {
+    buf: Vec<u8>,
+}
+
+impl ProtoWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(mut self, field: u32, wire_type: u8) -> Self {
+        write_varint(&mut self.buf, ((field as u64) << 3) | wire_type as u64);
+        self
+    }
+
+    /// Campo varint (uint32/uint64/enum)
+    pub fn uint(self, field: u32, value: u64) -> Self {
+        let mut w = self.key(field, 0);
+        write_varint(&mut w.buf, value);
+        w
+    }
+
+    /// Campo float (fixed32)
+    pub fn float(self, field: u32, value: f32) -> Self {
+        let mut w = self.key(field, 5);
+        w.buf.extend_from_slice(&value.to_le_bytes());
+        w
+    }
+
+    /// Campo double (fixed64)
+    pub fn double(self, field: u32, value: f64) -> Self {
+        let mut w = self.key(field, 1);
+        w.buf.extend_from_slice(&value.to_le_bytes());
+        w
+    }
+
+    /// Campo length-delimited (bytes)
+    pub fn bytes(self, field: u32, value: &[u8]) -> Self {
+        let mut w = self.key(field, 2);
+        write_varint(&mut w.buf, value.len() as u64);
+        w.buf.extend_from_slice(value);
+        w
+    }
+
+    pub fn string(self, field: u32, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Mensagem aninhada
+    pub fn message(self, field: u32, value: ProtoWriter) -> Self {
+        self.bytes(field, &value.buf)
+    }
+
+    /// Campo repeated varint no formato packed
+    pub fn packed(self, field: u32, values: &[u64]) -> Self {
+        let mut packed = Vec::new();
+        for v in values {
+            write_varint(&mut packed, *v);
+        }
+        self.bytes(field, &packed)
+    }
+
+    /// TSP.Reference { identifier = 1 }
+    pub fn reference(self, field: u32, identifier: u64) -> Self {
+        self.message(field, ProtoWriter::new().uint(1, identifier))
+    }
+
+    /// TSP.Size { width = 1, height = 2 }
+    pub fn size(self, field: u32, width: f32, height: f32) -> Self {
+        self.message(field, ProtoWriter::new().float(1, width).float(2, height))
+    }
+
+    /// TSP.Point { x = 1, y = 2 }
+    pub fn point(self, field: u32, x: f32, y: f32) -> Self {
+        self.message(field, ProtoWriter::new().float(1, x).float(2, y))
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Um objeto serializado dentro de um arquivo .iwa
+pub struct IwaObject {
+    /// Identificador único do objeto no documento
+    pub identifier: u64,
+    /// Tipo da mensagem (ex: 5 = KN.SlideArchive)
+    pub message_type: u32,
+    pub payload: ProtoWriter,
+    /// Objetos referenciados por este (TSP.Reference)
+    pub object_references: Vec<u64>,
+    /// Arquivos de Data/ referenciados por este (TSP.DataReference)
+    pub data_references: Vec<u64>,
+}
+
+impl IwaObject {
+    pub fn new(identifier: u64, message_type: u32, payload: ProtoWriter) -> Self {
+        Self {
+            identifier,
+            message_type,
+            payload,
+            object_references: Vec::new(),
+            data_references: Vec::new(),
+        }
+    }
+
+    pub fn with_refs(mut self, refs: &[u64]) -> Self {
+        self.object_references.extend_from_slice(refs);
+        self
+    }
+
+    pub fn with_data_refs(mut self, refs: &[u64]) -> Self {
+        self.data_references.extend_from_slice(refs);
+        self
+    }
+}
+
+/// Serializa uma lista de objetos no formato completo de um arquivo .iwa
+pub fn encode_iwa(objects: Vec<IwaObject>) -> Vec<u8> {
+    let mut stream = Vec::new();
+
+    for object in objects {
+        let payload = object.payload.into_bytes();
+
+        // TSP.MessageInfo
+        let mut message_info = ProtoWriter::new()
+            .uint(1, object.message_type as u64)
+            .packed(2, &[1, 0, 5])
+            .uint(3, payload.len() as u64);
+        if !object.object_references.is_empty() {
+            message_info = message_info.packed(5, &object.object_references);
+        }
+        if !object.data_references.is_empty() {
+            message_info = message_info.packed(6, &object.data_references);
+        }
+
+        // TSP.ArchiveInfo
+        let archive_info = ProtoWriter::new()
+            .uint(1, object.identifier)
+            .message(2, message_info)
+            .into_bytes();
+
+        write_varint(&mut stream, archive_info.len() as u64);
+        stream.extend_from_slice(&archive_info);
+        stream.extend_from_slice(&payload);
+    }
+
+    frame_snappy(&stream)
+}
+
+/// Envolve os dados nos blocos do container IWA: `0x00` + tamanho (24 bits LE) + snappy
+///
+/// Cada bloco é um stream snappy válido composto apenas de literais, o que
+/// dispensa um compressor e continua legível por qualquer decodificador.
+fn frame_snappy(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / IWA_CHUNK_SIZE * 8 + 8);
+
+    for chunk in data.chunks(IWA_CHUNK_SIZE) {
+        let mut block = Vec::with_capacity(chunk.len() + 8);
+        write_varint(&mut block, chunk.len() as u64);
+
+        let len = chunk.len() - 1;
+        if len < 60 {
+            block.push((len as u8) << 2);
+        } else if len < 0x100 {
+            block.push(60 << 2);
+            block.push(len as u8);
+        } else {
+            block.push(61 << 2);
+            block.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        block.extend_from_slice(chunk);
+
+        out.push(0x00);
+        out.extend_from_slice(&(block.len() as u32).to_le_bytes()[..3]);
+        out.extend_from_slice(&block);
+    }
+
+    out
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Campo protobuf lido: varint, fixed64, fixed32 ou length-delimited
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum Value {
+        Varint(u64),
+        Fixed64(u64),
+        Fixed32(u32),
+        Bytes(Vec<u8>),
+    }
+
+    /// Objeto lido de um .iwa, para comparar com o esperado
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct Archive {
+        pub identifier: u64,
+        pub message_type: u32,
+        pub object_references: Vec<u64>,
+        pub data_references: Vec<u64>,
+        pub payload: Vec<u8>,
+    }
+
+    pub(crate) fn read_varint(data: &[u8], pos: &mut usize) -> u64 {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = data[*pos];
+            *pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+        }
+        panic!("varint com mais de 10 bytes");
+    }
+
+    pub(crate) fn fields(data: &[u8]) -> Vec<(u32, Value)> {
+        let mut pos = 0;
+        let mut result = Vec::new();
+        while pos < data.len() {
+            let key = read_varint(data, &mut pos);
+            let value = match key & 7 {
+                0 => Value::Varint(read_varint(data, &mut pos)),
+                2 => {
+                    let len = read_varint(data, &mut pos) as usize;
+                    pos += len;
+                    Value::Bytes(data[pos - len..pos].to_vec())
+                }
+                1 => {
+                    pos += 8;
+                    Value::Fixed64(u64::from_le_bytes(data[pos - 8..pos].try_into().unwrap()))
+                }
+                5 => {
+                    pos += 4;
+                    Value::Fixed32(u32::from_le_bytes(data[pos - 4..pos].try_into().unwrap()))
+                }
+                wire_type => panic!("wire type inesperado {}", wire_type),
+            };
+            result.push(((key >> 3) as u32, value));
+        }
+        result
+    }
+
+    fn packed(data: &[u8]) -> Vec<u64> {
+        let mut pos = 0;
+        std::iter::from_fn(|| (pos < data.len()).then(|| read_varint(data, &mut pos))).collect()
+    }
+
+    /// Desfaz os blocos do container, conferindo que cada um é um stream snappy só de literais
+    pub(crate) fn unframe(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            assert_eq!(data[pos], 0x00, "tipo de bloco no byte {}", pos);
+            let len = u32::from_le_bytes([data[pos + 1], data[pos + 2], data[pos + 3], 0]) as usize;
+            let block = &data[pos + 4..pos + 4 + len];
+            pos += 4 + len;
+
+            let mut at = 0;
+            let expected = read_varint(block, &mut at) as usize;
+            assert!(expected <= IWA_CHUNK_SIZE, "bloco com {} bytes", expected);
+            let start = out.len();
+            while at < block.len() {
+                let tag = block[at] as usize;
+                at += 1;
+                assert_eq!(tag & 3, 0, "só literais são gravados");
+                let literal = match tag >> 2 {
+                    n @ 0..=59 => n + 1,
+                    n => {
+                        let extra = n - 59;
+                        let mut bytes = [0u8; 4];
+                        bytes[..extra].copy_from_slice(&block[at..at + extra]);
+                        at += extra;
+                        u32::from_le_bytes(bytes) as usize + 1
+                    }
+                };
+                out.extend_from_slice(&block[at..at + literal]);
+                at += literal;
+            }
+            assert_eq!(out.len() - start, expected);
+        }
+        out
+    }
+
+    pub(crate) fn decode_iwa(data: &[u8]) -> Vec<Archive> {
+        let stream = unframe(data);
+        let mut pos = 0;
+        let mut archives = Vec::new();
+        while pos < stream.len() {
+            let len = read_varint(&stream, &mut pos) as usize;
+            let mut archive = Archive {
+                identifier: 0,
+                message_type: 0,
+                object_references: Vec::new(),
+                data_references: Vec::new(),
+                payload: Vec::new(),
+            };
+            let mut payload_len = 0;
+            for (field, value) in fields(&stream[pos..pos + len]) {
+                match (field, value) {
+                    (1, Value::Varint(identifier)) => archive.identifier = identifier,
+                    (2, Value::Bytes(info)) => {
+                        for (field, value) in fields(&info) {
+                            match (field, value) {
+                                (1, Value::Varint(message_type)) => archive.message_type = message_type as u32,
+                                (3, Value::Varint(len)) => payload_len = len as usize,
+                                (5, Value::Bytes(refs)) => archive.object_references = packed(&refs),
+                                (6, Value::Bytes(refs)) => archive.data_references = packed(&refs),
+                                _ => {}
+                            }
+                        }
+                    }
+                    other => panic!("campo inesperado no ArchiveInfo: {:?}", other),
+                }
+            }
+            pos += len;
+            archive.payload = stream[pos..pos + payload_len].to_vec();
+            pos += payload_len;
+            archives.push(archive);
+        }
+        archives
+    }
+
+    #[test]
+    fn varint_encoding() {
+        for (value, expected) in [
+            (0u64, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (300, vec![0xac, 0x02]),
+            (u64::MAX, vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+        ] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(buf, expected, "{}", value);
+            let mut pos = 0;
+            assert_eq!(read_varint(&buf, &mut pos), value);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn snappy_round_trip_across_chunk_boundaries() {
+        for size in [0, 1, 60, 61, 256, 257, IWA_CHUNK_SIZE - 1, IWA_CHUNK_SIZE, IWA_CHUNK_SIZE + 1, 3 * IWA_CHUNK_SIZE + 17] {
+            let data: Vec<u8> = (0..size).map(|i| (i * 7 % 251) as u8).collect();
+            let framed = frame_snappy(&data);
+            assert_eq!(unframe(&framed), data, "{} bytes", size);
+            assert_eq!(framed.is_empty(), size == 0);
+        }
+    }
+
+    #[test]
+    fn snappy_block_count() {
+        let framed = frame_snappy(&vec![0xab; 2 * IWA_CHUNK_SIZE + 1]);
+        // Cabeçalho do primeiro bloco: tipo 0 + tamanho de 64 KiB + varint (3 bytes) + tag de 3 bytes
+        assert_eq!(&framed[..4], &[0x00, 0x06, 0x00, 0x01]);
+        let mut pos = 0;
+        let mut blocks = 0;
+        while pos < framed.len() {
+            let len = u32::from_le_bytes([framed[pos + 1], framed[pos + 2], framed[pos + 3], 0]) as usize;
+            pos += 4 + len;
+            blocks += 1;
+        }
+        assert_eq!(blocks, 3);
+    }
+
+    #[test]
+    fn archive_layout_golden() {
+        let objects = vec![
+            IwaObject::new(1, 5, ProtoWriter::new().uint(1, 7)),
+            IwaObject::new(300, 3005, ProtoWriter::new().reference(2, 1)).with_refs(&[1]).with_data_refs(&[9]),
+        ];
+        let encoded = encode_iwa(objects);
+        assert_eq!(
+            encoded,
+            [
+                // Bloco de 44 bytes: 42 descomprimidos, um literal só
+                &[0x00, 0x2c, 0x00, 0x00, 0x2a, 0xa4][..],
+                // ArchiveInfo { identifier: 1, MessageInfo { type: 5, version: [1, 0, 5], length: 2 } }
+                &[0x0d, 0x08, 0x01, 0x12, 0x09, 0x08, 0x05, 0x12, 0x03, 0x01, 0x00, 0x05, 0x18, 0x02],
+                &[0x08, 0x07],
+                // ArchiveInfo { identifier: 300, MessageInfo { type: 3005, ..., length: 4, refs: [1], data: [9] } }
+                &[0x15, 0x08, 0xac, 0x02, 0x12, 0x10, 0x08, 0xbd, 0x17, 0x12, 0x03, 0x01, 0x00, 0x05, 0x18, 0x04],
+                &[0x2a, 0x01, 0x01, 0x32, 0x01, 0x09],
+                &[0x12, 0x02, 0x08, 0x01],
+            ]
+            .concat()
+        );
+        assert_eq!(
+            format!("{:?}", decode_iwa(&encoded)),
+            "[Archive { identifier: 1, message_type: 5, object_references: [], data_references: [], payload: [8, 7] }, \
+             Archive { identifier: 300, message_type: 3005, object_references: [1], data_references: [9], payload: [18, 2, 8, 1] }]"
+        );
+    }
+
+    #[test]
+    fn proto_writer_fields() {
+        let bytes = ProtoWriter::new()
+            .uint(1, 150)
+            .float(2, 1.5)
+            .string(3, "ok")
+            .packed(4, &[1, 300])
+            .size(5, 2.0, 3.0)
+            .into_bytes();
+        assert_eq!(
+            fields(&bytes),
+            vec![
+                (1, Value::Varint(150)),
+                (2, Value::Fixed32(1.5f32.to_bits())),
+                (3, Value::Bytes(b"ok".to_vec())),
+                (4, Value::Bytes(vec![0x01, 0xac, 0x02])),
+                (5, Value::Bytes(ProtoWriter::new().float(1, 2.0).float(2, 3.0).into_bytes())),
+            ]
+        );
+    }
+}
@@ -1,49 +1,201 @@
-//! Módulo para controle do Apple Keynote via AppleScript
-//! Cria apresentações editáveis diretamente no Keynote
+//! Módulo para geração de apresentações Keynote
+//! Controla o Keynote via AppleScript ou escreve o pacote .key diretamente
 
+use crate::conversion::OutputFormat;
+use crate::error::Pdf2KeyError;
+use crate::editable::SlideObject;
+use crate::keynote_native;
+use crate::layout::{PixelMapping, SlideLayout};
+use crate::links::PixelRect;
+use crate::slide::Slide;
 use anyhow::{Context, Result};
 use std::path::Path;
 use std::process::Command;
 
-/// Controla o Keynote via AppleScript para criar apresentações
+/// Forma de gerar o arquivo .key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeynoteBackend {
+    /// Automatiza o Keynote.app via osascript (somente macOS)
+    AppleScript,
+    /// Escreve o pacote .key (IWA) diretamente, em qualquer sistema
+    Native,
+}
+
+impl Default for KeynoteBackend {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::AppleScript
+        } else {
+            Self::Native
+        }
+    }
+}
+
+/// Cria apresentações Keynote com uma imagem por slide
 pub struct KeynoteBuilder {
-    slide_images: Vec<String>,
+    slides: Vec<Slide>,
+    backend: KeynoteBackend,
+    layout: SlideLayout,
+}
+
+impl Default for KeynoteBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl KeynoteBuilder {
     /// Cria um novo builder para apresentações Keynote
     pub fn new() -> Self {
         Self {
-            slide_images: Vec::new(),
+            slides: Vec::new(),
+            backend: KeynoteBackend::default(),
+            layout: SlideLayout::default(),
         }
     }
 
+    /// Define o backend usado para gerar o arquivo
+    pub fn with_backend(mut self, backend: KeynoteBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Tamanho dos slides (em pontos), encaixe das páginas e cor de fundo
+    ///
+    /// O Keynote não tem cor de fundo por script: onde a página não cobre o slide, uma
+    /// imagem da cor de fundo fica por baixo dela.
+    pub fn with_layout(mut self, layout: SlideLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
     /// Adiciona uma imagem como um novo slide
     pub fn add_slide(&mut self, image_path: &Path) {
-        self.slide_images.push(image_path.to_string_lossy().to_string());
+        self.push_slide(Slide::new(image_path));
+    }
+
+    /// Adiciona um slide com notas e camadas de build
+    ///
+    /// As camadas ficam empilhadas sobre a imagem, cada uma com um build de entrada. O
+    /// AppleScript do Keynote não cria builds: com camadas, a apresentação é escrita pelo
+    /// backend nativo e aberta e salva pelo Keynote, sem os SVGs e os objetos editáveis.
+    /// Seções viram divisórias puladas antes do slide; o Keynote não tem nome de slide,
+    /// então `title` é ignorado. Links também são ignorados (só o PowerPoint e o Impress os gravam).
+    pub fn push_slide(&mut self, slide: Slide) {
+        self.slides.push(slide);
     }
 
-    /// Constrói e salva a apresentação no Keynote
+    /// Constrói e salva a apresentação
     pub fn build(&self, output_path: &Path) -> Result<()> {
-        if self.slide_images.is_empty() {
+        self.build_with_progress(output_path, &mut |_| Ok(()))
+    }
+
+    /// Constrói e salva a apresentação, chamando `on_slide` com o número de slides já gravados
+    ///
+    /// Um erro retornado por `on_slide` interrompe a geração (ex: cancelamento).
+    /// Pelo AppleScript os slides são criados de uma vez, então `on_slide` só é chamado ao final.
+    pub fn build_with_progress(&self, output_path: &Path, on_slide: &mut dyn FnMut(usize) -> Result<()>) -> Result<()> {
+        if self.slides.is_empty() {
             anyhow::bail!("Nenhum slide foi adicionado");
         }
 
-        let output_path_str = output_path.to_string_lossy().to_string();
-        
-        println!("[Keynote] Criando apresentação...");
+        match self.backend {
+            KeynoteBackend::AppleScript if self.slides.iter().any(|s| !s.builds.is_empty()) => {
+                let package = tempfile::Builder::new().suffix(".key").tempfile().context("Falha ao criar o pacote temporário")?;
+                self.build_native(package.path(), on_slide)?;
+                resave_with_keynote(package.path(), output_path)
+            }
+            KeynoteBackend::AppleScript => {
+                self.build_applescript(output_path)?;
+                on_slide(self.slides.len())
+            }
+            KeynoteBackend::Native => self.build_native(output_path, on_slide),
+        }
+    }
+
+    /// Escreve o pacote .key sem depender do Keynote
+    fn build_native(&self, output_path: &Path, on_slide: &mut dyn FnMut(usize) -> Result<()>) -> Result<()> {
+        let objects: usize = self.slides.iter().map(|s| s.objects.len()).sum();
+        if objects > 0 {
+            log::debug!("{} objetos editáveis ignorados (o pacote nativo só tem a imagem do slide)", objects);
+        }
+        if self.slides.iter().any(|s| s.vector.is_some()) {
+            log::debug!("Slides vetoriais não suportados no pacote nativo; usando as imagens");
+        }
+
+        keynote_native::write_package(&self.slides, output_path, &self.layout, on_slide)
+            .context("Falha ao escrever o pacote .key")?;
+
+        log::info!("Pacote .key criado: {}", output_path.display());
+        Ok(())
+    }
+
+    /// Constrói e salva a apresentação no Keynote via AppleScript
+    fn build_applescript(&self, output_path: &Path) -> Result<()> {
+        let output_path_str = applescript_escape(&output_path.to_string_lossy());
 
-        // Gera lista de imagens para o AppleScript
-        let image_list: Vec<String> = self.slide_images
+        let first = image::image_dimensions(&self.slides[0].image).context("Falha ao ler imagem do primeiro slide")?;
+        let (slide_w, slide_h) = self.layout.slide_size(first);
+        let aspect = slide_w as f64 / slide_h as f64;
+
+        // Cor de fundo: uma imagem pequena esticada sobre o slide, gravada só se alguma página precisar
+        let background = tempfile::Builder::new().suffix(".png").tempfile().context("Falha ao criar imagem de fundo")?;
+        let background_path = background.path().to_string_lossy().to_string();
+        let mut background_written = false;
+
+        // Gera lista de imagens para o AppleScript: cada item tem o fundo (se a página não
+        // cobre o slide), a imagem do slide (o SVG, se houver) e as camadas de build, estas
+        // empilhadas na mesma posição, em frações do slide
+        let mut image_list = Vec::with_capacity(self.slides.len());
+        let mut mappings = Vec::with_capacity(self.slides.len());
+        for (i, s) in self.slides.iter().enumerate() {
+            let size = image::image_dimensions(&s.image).context(format!("Falha ao ler imagem do slide {}", i + 1))?;
+            let placement = self.layout.place(aspect, size);
+            let mapping = placement.mapping((1.0, 1.0), size);
+            let mut records = Vec::with_capacity(2 + s.builds.len());
+            if !placement.covers() {
+                if !background_written {
+                    image::RgbImage::from_pixel(16, 16, image::Rgb(self.layout.background))
+                        .save_with_format(background.path(), image::ImageFormat::Png)
+                        .context("Falha ao gravar imagem de fundo")?;
+                    background_written = true;
+                }
+                records.push(image_record(&background_path, (0.0, 0.0, 1.0, 1.0)));
+            }
+            let frame = mapping.image(size);
+            records.extend(
+                std::iter::once(s.vector.as_ref().unwrap_or(&s.image))
+                    .chain(&s.builds)
+                    .map(|p| image_record(&p.to_string_lossy(), frame)),
+            );
+            image_list.push(format!("{{{}}}", records.join(", ")));
+            mappings.push(mapping);
+        }
+        let image_list_str = image_list.join(", ");
+        let notes_list: Vec<String> = self.slides
             .iter()
-            .map(|p| format!("\"{}\"", p))
+            .map(|s| format!("\"{}\"", applescript_escape(&s.notes)))
             .collect();
-        let image_list_str = image_list.join(", ");
+        let notes_list_str = notes_list.join(", ");
+        let section_list: Vec<String> = self.slides
+            .iter()
+            .map(|s| format!("\"{}\"", applescript_escape(s.section.as_deref().unwrap_or(""))))
+            .collect();
+        let section_list_str = section_list.join(", ");
+        let object_list: Vec<String> = self.slides
+            .iter()
+            .zip(&mappings)
+            .map(|(slide, mapping)| object_records(slide, mapping))
+            .collect();
+        let object_list_str = object_list.join(", ");
 
         // AppleScript robusto - usa 'launch' para rodar em background
         let applescript = format!(
             r#"
 set imageList to {{{image_list}}}
+set notesList to {{{notes_list}}}
+set sectionList to {{{section_list}}}
+set objectList to {{{object_list}}}
 set outputPath to "{output_path}"
 
 -- Verifica se Keynote já está rodando
@@ -58,27 +210,72 @@ end tell
 launch application "Keynote"
 
 tell application "Keynote"
-    set theDoc to make new document
+    set theDoc to make new document with properties {{width:{slide_w}, height:{slide_h}}}
     
     set slideWidth to width of theDoc
     set slideHeight to height of theDoc
     
+    -- O documento novo já tem um slide, usado pelo primeiro slide ou divisória
+    set firstSlideFree to true
+    
     repeat with i from 1 to count of imageList
-        set imagePath to item i of imageList
-        set imageFile to (POSIX file imagePath) as alias
+        set slideImages to item i of imageList
+        set sectionTitle to item i of sectionList
+        
+        -- Divisória de seção: slide pulado com o título
+        if sectionTitle is not "" then
+            if firstSlideFree then
+                set dividerSlide to slide 1 of theDoc
+                set firstSlideFree to false
+            else
+                set dividerSlide to make new slide at end of slides of theDoc
+            end if
+            tell dividerSlide
+                make new text item with properties {{object text:sectionTitle}}
+            end tell
+            set skipped of dividerSlide to true
+        end if
         
-        if i is 1 then
+        if firstSlideFree then
             set currentSlide to slide 1 of theDoc
+            set firstSlideFree to false
         else
             set currentSlide to make new slide at end of slides of theDoc
         end if
         
-        tell currentSlide
-            set theImage to make new image with properties {{file:imageFile}}
-            set width of theImage to slideWidth
-            set height of theImage to slideHeight
-            set position of theImage to {{0, 0}}
-        end tell
+        -- Imagens: posições e tamanhos em frações do slide
+        repeat with theRecord in slideImages
+            set imageFile to (POSIX file (imgPath of theRecord)) as alias
+            tell currentSlide
+                set theImage to make new image with properties {{file:imageFile}}
+                set width of theImage to (imgW of theRecord) * slideWidth
+                set height of theImage to (imgH of theRecord) * slideHeight
+                set position of theImage to {{(imgX of theRecord) * slideWidth, (imgY of theRecord) * slideHeight}}
+            end tell
+        end repeat
+        
+        -- Objetos editáveis: posições e tamanhos em frações do slide
+        repeat with theObject in item i of objectList
+            tell currentSlide
+                if objKind of theObject is "image" then
+                    set theItem to make new image with properties {{file:(POSIX file (objPath of theObject)) as alias}}
+                else
+                    set theItem to make new text item with properties {{object text:objText of theObject}}
+                    tell object text of theItem
+                        set size to (objSize of theObject) * slideHeight
+                        set color to objColor of theObject
+                        try
+                            set font to objFont of theObject
+                        end try
+                    end tell
+                end if
+                set width of theItem to (objW of theObject) * slideWidth
+                set height of theItem to (objH of theObject) * slideHeight
+                set position of theItem to {{(objX of theObject) * slideWidth, (objY of theObject) * slideHeight}}
+            end tell
+        end repeat
+        
+        set presenter notes of currentSlide to item i of notesList
     end repeat
     
     save theDoc in POSIX file outputPath
@@ -91,24 +288,129 @@ if not wasRunning then
 end if
 "#,
             image_list = image_list_str,
-            output_path = output_path_str
+            notes_list = notes_list_str,
+            section_list = section_list_str,
+            object_list = object_list_str,
+            output_path = output_path_str,
+            slide_w = slide_w,
+            slide_h = slide_h
         );
 
-        println!("[Keynote] Executando AppleScript...");
+        run_osascript(&applescript)?;
+        log::info!("Apresentação Keynote criada: {}", output_path.display());
+        Ok(())
+    }
+}
 
-        let output = Command::new("osascript")
-            .arg("-e")
-            .arg(&applescript)
-            .output()
-            .context("Falha ao executar osascript")?;
+/// Abre no Keynote o pacote escrito pelo backend nativo e o salva em `output_path`
+fn resave_with_keynote(package: &Path, output_path: &Path) -> Result<()> {
+    let applescript = format!(
+        r#"
+set inputPath to "{input_path}"
+set outputPath to "{output_path}"
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            println!("[Keynote] ERRO: {}", stderr);
-            anyhow::bail!("Erro no Keynote (Verifique permissões de acesso): {}", stderr);
+set wasRunning to false
+tell application "System Events"
+    if exists (processes where name is "Keynote") then
+        set wasRunning to true
+    end if
+end tell
+
+launch application "Keynote"
+
+tell application "Keynote"
+    set theDoc to open (POSIX file inputPath)
+    save theDoc in POSIX file outputPath
+    close theDoc saving no
+end tell
+
+if not wasRunning then
+    tell application "Keynote" to quit
+end if
+"#,
+        input_path = applescript_escape(&package.to_string_lossy()),
+        output_path = applescript_escape(&output_path.to_string_lossy()),
+    );
+    run_osascript(&applescript)?;
+    log::info!("Apresentação Keynote com builds criada: {}", output_path.display());
+    Ok(())
+}
+
+fn run_osascript(applescript: &str) -> Result<()> {
+    log::debug!("Executando AppleScript");
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(applescript)
+        .output()
+        .context("Falha ao executar osascript")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Tipado para que a interface possa sugerir verificar as permissões de automação
+        return Err(Pdf2KeyError::BackendFailed {
+            format: OutputFormat::Keynote,
+            stderr: stderr.into_owned(),
         }
+        .into());
+    }
+    Ok(())
+}
 
-        println!("[Keynote] ✓ Apresentação criada com sucesso!");
-        Ok(())
+/// Registro AppleScript de uma imagem do slide; `frame` em frações do slide
+fn image_record(path: &str, (x, y, w, h): (f64, f64, f64, f64)) -> String {
+    format!("{{imgPath:\"{}\", imgX:{}, imgY:{}, imgW:{}, imgH:{}}}", applescript_escape(path), x, y, w, h)
+}
+
+/// Lista AppleScript com os objetos editáveis do slide, em frações do slide
+///
+/// `mapping` converte pixels da imagem do slide em frações do slide.
+fn object_records(slide: &Slide, mapping: &PixelMapping) -> String {
+    if slide.objects.is_empty() {
+        return "{}".to_string();
     }
+    let fraction = |rect: &PixelRect| {
+        let (x, y, w, h) = mapping.rect(rect);
+        format!("objX:{}, objY:{}, objW:{}, objH:{}", x, y, w, h)
+    };
+    let records: Vec<String> = slide
+        .objects
+        .iter()
+        .map(|object| match object {
+            SlideObject::Image(placed) => format!(
+                "{{objKind:\"image\", objPath:\"{}\", {}}}",
+                applescript_escape(&placed.path.to_string_lossy()),
+                fraction(&placed.rect)
+            ),
+            SlideObject::Text(text) => {
+                // Nome PostScript com o estilo (ex: "Helvetica-Bold"); se o Keynote não
+                // conhecer a fonte, fica a padrão
+                let style = match (text.bold, text.italic) {
+                    (true, true) => "-BoldItalic",
+                    (true, false) => "-Bold",
+                    (false, true) => "-Italic",
+                    (false, false) => "",
+                };
+                // Cores do AppleScript vão de 0 a 65535
+                let [r, g, b] = text.color.map(|c| c as u32 * 257);
+                format!(
+                    "{{objKind:\"text\", objText:\"{}\", objFont:\"{}{}\", objSize:{}, objColor:{{{}, {}, {}}}, {}}}",
+                    applescript_escape(&text.text),
+                    applescript_escape(&text.font),
+                    style,
+                    text.size as f64 * mapping.scale.1,
+                    r,
+                    g,
+                    b,
+                    fraction(&text.rect)
+                )
+            }
+        })
+        .collect();
+    format!("{{{}}}", records.join(", "))
+}
+
+/// Escapa texto para um literal de string do AppleScript
+fn applescript_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
 }
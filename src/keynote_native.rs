@@ -0,0 +1,659 @@
+//! Escrita nativa de pacotes .key (sem Keynote.app nem osascript)
+//! Gera o container zip com Index/*.iwa, Data/, Metadata/ e previews
+
+use crate::iwa::{encode_iwa, IwaObject, ProtoWriter};
+use crate::layout::SlideLayout;
+use crate::slide::Slide;
+use anyhow::{Context, Result};
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// Tipos de mensagem (registro de tipos do Keynote)
+const KN_DOCUMENT_ARCHIVE: u32 = 1;
+const KN_SHOW_ARCHIVE: u32 = 2;
+const KN_UI_STATE_ARCHIVE: u32 = 3;
+const KN_SLIDE_NODE_ARCHIVE: u32 = 4;
+const KN_SLIDE_ARCHIVE: u32 = 5;
+const KN_BUILD_ARCHIVE: u32 = 8;
+const KN_THEME_ARCHIVE: u32 = 10;
+const KN_NOTE_ARCHIVE: u32 = 15;
+const TSS_STYLESHEET_ARCHIVE: u32 = 401;
+const TSWP_STORAGE_ARCHIVE: u32 = 2001;
+const TSD_IMAGE_ARCHIVE: u32 = 3005;
+const TSD_MEDIA_STYLE_ARCHIVE: u32 = 3016;
+const TSP_PACKAGE_METADATA: u32 = 11006;
+
+// Identificadores fixos dos objetos do documento
+const ID_DOCUMENT: u64 = 1;
+const ID_SHOW: u64 = 2;
+const ID_UI_STATE: u64 = 3;
+const ID_THEME: u64 = 4;
+const ID_STYLESHEET: u64 = 5;
+const ID_MEDIA_STYLE: u64 = 6;
+const ID_PACKAGE_METADATA: u64 = 7;
+/// Primeiro identificador dos objetos de slides, alocados em sequência a partir daqui
+const ID_FIRST_SLIDE: u64 = 100;
+
+/// TSWP.StorageArchive.KindType das notas do apresentador
+const STORAGE_KIND_NOTE: u64 = 4;
+
+/// Efeito de entrada das camadas de build ("Aparecer")
+const BUILD_EFFECT_APPEAR: &str = "apple:appear";
+/// Duração da animação de cada camada, em segundos
+const BUILD_DURATION: f64 = 0.5;
+
+/// Tamanho padrão dos slides (16:9, igual ao tema padrão do Keynote)
+pub const DEFAULT_SLIDE_SIZE: (u32, u32) = (1920, 1080);
+
+/// Um slide com imagens empilhadas na ordem
+struct NativeSlide {
+    node_id: u64,
+    slide_id: u64,
+    /// Fundo (se a página não cobre o slide), imagem da página e camadas de build
+    images: Vec<NativeImage>,
+    /// Animação de entrada de cada camada, na ordem dos cliques
+    builds: Vec<NativeBuild>,
+    note_id: u64,
+    note_storage_id: u64,
+    /// Notas do apresentador (vazio = sem notas)
+    notes: String,
+    /// Slide pulado na apresentação (divisória de seção)
+    skipped: bool,
+}
+
+/// Build de entrada (KN.BuildArchive) de uma das imagens do slide
+struct NativeBuild {
+    build_id: u64,
+    image_id: u64,
+}
+
+struct NativeImage {
+    image_id: u64,
+    data_id: u64,
+    data_name: String,
+    path: PathBuf,
+    size: (u32, u32),
+    /// Posição e tamanho no slide em pontos: (x, y, largura, altura)
+    frame: (f32, f32, f32, f32),
+}
+
+/// Escreve um pacote .key completo com uma imagem por slide
+///
+/// As camadas de build de cada slide são empilhadas sobre a imagem, cada uma com um build
+/// de entrada que a faz aparecer com um clique, na ordem. Cada seção ganha antes do seu
+/// primeiro slide uma divisória pulada, vazia, com o título da seção nas notas.
+/// O tamanho do slide e o encaixe das páginas vêm de `layout`; onde a página não cobre
+/// o slide, uma imagem da cor de fundo fica por baixo.
+/// `on_slide` recebe o número de slides já gravados; um erro retornado interrompe a escrita.
+pub fn write_package(
+    input_slides: &[Slide],
+    output_path: &Path,
+    layout: &SlideLayout,
+    on_slide: &mut dyn FnMut(usize) -> Result<()>,
+) -> Result<()> {
+    let first = match input_slides.first() {
+        Some(slide) => image::image_dimensions(&slide.image).context("Falha ao ler imagem do primeiro slide")?,
+        None => layout.size,
+    };
+    let slide_size = layout.slide_size(first);
+    let (slide_w, slide_h) = (slide_size.0 as f64, slide_size.1 as f64);
+    let aspect = slide_w / slide_h;
+
+    // Cor de fundo: uma imagem pequena esticada sobre o slide, gravada só se alguma página
+    // precisar e guardada uma única vez em Data/ para todos os slides
+    let background = tempfile::Builder::new().suffix(".png").tempfile().context("Falha ao criar imagem de fundo")?;
+    let mut background_data = None;
+
+    let mut next_id = ID_FIRST_SLIDE;
+    let mut allocate = || {
+        next_id += 1;
+        next_id - 1
+    };
+
+    let mut slides = Vec::with_capacity(input_slides.len());
+    for (i, slide) in input_slides.iter().enumerate() {
+        if let Some(section) = &slide.section {
+            let (node_id, slide_id, note_id, note_storage_id) = (allocate(), allocate(), allocate(), allocate());
+            slides.push(NativeSlide {
+                node_id,
+                slide_id,
+                images: Vec::new(),
+                builds: Vec::new(),
+                note_id,
+                note_storage_id,
+                notes: section.clone(),
+                skipped: true,
+            });
+        }
+
+        let (node_id, slide_id, note_id, note_storage_id) = (allocate(), allocate(), allocate(), allocate());
+        let mut images = Vec::with_capacity(2 + slide.builds.len());
+        let mut builds = Vec::with_capacity(slide.builds.len());
+        let page_size = image::image_dimensions(&slide.image)
+            .context(format!("Falha ao ler imagem do slide {}", i + 1))?;
+        let placement = layout.place(aspect, page_size);
+        if !placement.covers() {
+            let data_id = match background_data {
+                Some(data_id) => data_id,
+                None => {
+                    RgbImage::from_pixel(16, 16, Rgb(layout.background))
+                        .save_with_format(background.path(), ImageFormat::Png)
+                        .context("Falha ao gravar imagem de fundo")?;
+                    *background_data.insert(allocate())
+                }
+            };
+            images.push(NativeImage {
+                image_id: allocate(),
+                data_id,
+                data_name: format!("background-{}.png", data_id),
+                path: background.path().to_path_buf(),
+                size: (16, 16),
+                frame: (0.0, 0.0, slide_w as f32, slide_h as f32),
+            });
+        }
+        let (x, y, w, h) = placement.mapping((slide_w, slide_h), page_size).image(page_size);
+        let frame = (x as f32, y as f32, w as f32, h as f32);
+        for (k, path) in std::iter::once(&slide.image).chain(&slide.builds).enumerate() {
+            let size = image::image_dimensions(path)
+                .context(format!("Falha ao ler imagem do slide {}", i + 1))?;
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("png");
+            let (image_id, data_id) = (allocate(), allocate());
+            let data_name = match k {
+                0 => format!("slide_{:04}-{}.{}", i + 1, data_id, extension),
+                k => format!("slide_{:04}_build_{:02}-{}.{}", i + 1, k, data_id, extension),
+            };
+            images.push(NativeImage { image_id, data_id, data_name, path: path.clone(), size, frame });
+            if k > 0 {
+                builds.push(NativeBuild { build_id: allocate(), image_id });
+            }
+        }
+        slides.push(NativeSlide {
+            node_id,
+            slide_id,
+            images,
+            builds,
+            note_id,
+            note_storage_id,
+            notes: slide.notes.clone(),
+            skipped: false,
+        });
+    }
+    let last_id = allocate();
+
+    let file = File::create(output_path).context("Falha ao criar o arquivo .key")?;
+    let mut zip = ZipWriter::new(file);
+    // O Keynote espera entradas sem compressão
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    // Data/: cada arquivo uma vez, mesmo que vários slides o usem (o fundo)
+    let mut data_files: Vec<(&NativeImage, Vec<u8>)> = Vec::new();
+    for (i, slide) in slides.iter().filter(|s| !s.skipped).enumerate() {
+        for image in &slide.images {
+            if data_files.iter().any(|(written, _)| written.data_id == image.data_id) {
+                continue;
+            }
+            let bytes = std::fs::read(&image.path)?;
+            zip.start_file(format!("Data/{}", image.data_name), options)?;
+            zip.write_all(&bytes)?;
+            data_files.push((image, Sha1::digest(&bytes).to_vec()));
+        }
+        on_slide(i + 1)?;
+    }
+
+    // Index/
+    zip.start_file("Index/Document.iwa", options)?;
+    zip.write_all(&encode_iwa(document_objects(&slides, slide_size)))?;
+
+    zip.start_file("Index/Metadata.iwa", options)?;
+    zip.write_all(&encode_iwa(vec![package_metadata(&data_files, last_id)]))?;
+
+    // Metadata/
+    let document_uuid = make_uuid(output_path);
+    zip.start_file("Metadata/DocumentIdentifier", options)?;
+    zip.write_all(document_uuid.as_bytes())?;
+
+    zip.start_file("Metadata/Properties.plist", options)?;
+    zip.write_all(properties_plist(&document_uuid).as_bytes())?;
+
+    zip.start_file("Metadata/BuildVersionHistory.plist", options)?;
+    zip.write_all(BUILD_VERSION_HISTORY.as_bytes())?;
+
+    // Previews (a partir do primeiro slide)
+    if let Some(first) = input_slides.first() {
+        let image = image::open(&first.image).context("Falha ao gerar miniaturas")?;
+        for (name, width) in [("preview.jpg", 1024), ("preview-web.jpg", 480), ("preview-micro.jpg", 80)] {
+            zip.start_file(name, options)?;
+            zip.write_all(&thumbnail_jpeg(&image, width)?)?;
+        }
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Objetos de Index/Document.iwa: documento, show, tema e slides
+fn document_objects(slides: &[NativeSlide], slide_size: (u32, u32)) -> Vec<IwaObject> {
+    let (slide_w, slide_h) = (slide_size.0 as f32, slide_size.1 as f32);
+    let mut objects = Vec::new();
+
+    // KN.DocumentArchive
+    objects.push(
+        IwaObject::new(
+            ID_DOCUMENT,
+            KN_DOCUMENT_ARCHIVE,
+            ProtoWriter::new()
+                .message(1, ProtoWriter::new().message(1, ProtoWriter::new()))
+                .reference(3, ID_SHOW),
+        )
+        .with_refs(&[ID_SHOW]),
+    );
+
+    // KN.ShowArchive com a árvore de slides
+    let mut slide_tree = ProtoWriter::new();
+    for slide in slides {
+        slide_tree = slide_tree.reference(1, slide.node_id);
+    }
+    let mut show_refs = vec![ID_UI_STATE, ID_THEME, ID_STYLESHEET];
+    show_refs.extend(slides.iter().map(|s| s.node_id));
+    objects.push(
+        IwaObject::new(
+            ID_SHOW,
+            KN_SHOW_ARCHIVE,
+            ProtoWriter::new()
+                .reference(1, ID_UI_STATE)
+                .reference(2, ID_THEME)
+                .reference(3, ID_STYLESHEET)
+                .size(4, slide_w, slide_h)
+                .message(5, slide_tree),
+        )
+        .with_refs(&show_refs),
+    );
+
+    objects.push(IwaObject::new(ID_UI_STATE, KN_UI_STATE_ARCHIVE, ProtoWriter::new()));
+    objects.push(
+        IwaObject::new(
+            ID_THEME,
+            KN_THEME_ARCHIVE,
+            ProtoWriter::new().message(1, ProtoWriter::new().reference(1, ID_STYLESHEET)),
+        )
+        .with_refs(&[ID_STYLESHEET]),
+    );
+    objects.push(
+        IwaObject::new(
+            ID_STYLESHEET,
+            TSS_STYLESHEET_ARCHIVE,
+            ProtoWriter::new().reference(1, ID_MEDIA_STYLE),
+        )
+        .with_refs(&[ID_MEDIA_STYLE]),
+    );
+    objects.push(IwaObject::new(ID_MEDIA_STYLE, TSD_MEDIA_STYLE_ARCHIVE, ProtoWriter::new()));
+
+    for slide in slides {
+        // KN.SlideNodeArchive (isHidden = 4 para divisórias)
+        let mut node = ProtoWriter::new().reference(2, slide.slide_id);
+        if slide.skipped {
+            node = node.uint(4, 1);
+        }
+        objects.push(
+            IwaObject::new(slide.node_id, KN_SLIDE_NODE_ARCHIVE, node).with_refs(&[slide.slide_id]),
+        );
+
+        // KN.SlideArchive: style, transition, builds com os seus chunks (um clique cada),
+        // as imagens como drawables e as notas
+        let mut slide_archive = ProtoWriter::new()
+            .reference(1, ID_STYLESHEET)
+            .message(4, ProtoWriter::new());
+        let mut slide_refs = vec![ID_STYLESHEET];
+        for build in &slide.builds {
+            slide_archive = slide_archive.reference(5, build.build_id);
+            slide_refs.push(build.build_id);
+        }
+        for build in &slide.builds {
+            // KN.BuildChunkArchive { build, delay, duration, automatic = false }
+            let chunk = ProtoWriter::new()
+                .reference(1, build.build_id)
+                .double(2, 0.0)
+                .double(3, BUILD_DURATION)
+                .uint(4, 0);
+            slide_archive = slide_archive.message(6, chunk);
+        }
+        for image in &slide.images {
+            slide_archive = slide_archive.reference(7, image.image_id);
+            slide_refs.push(image.image_id);
+        }
+        if !slide.notes.is_empty() {
+            slide_archive = slide_archive.reference(27, slide.note_id);
+            slide_refs.push(slide.note_id);
+        }
+        objects.push(IwaObject::new(slide.slide_id, KN_SLIDE_ARCHIVE, slide_archive).with_refs(&slide_refs));
+
+        // KN.BuildArchive { drawable, delivery, duration, attributes, effect }: entrada da camada
+        for build in &slide.builds {
+            objects.push(
+                IwaObject::new(
+                    build.build_id,
+                    KN_BUILD_ARCHIVE,
+                    ProtoWriter::new()
+                        .reference(1, build.image_id)
+                        .string(2, "")
+                        .double(3, BUILD_DURATION)
+                        .message(4, ProtoWriter::new())
+                        .string(5, BUILD_EFFECT_APPEAR),
+                )
+                .with_refs(&[build.image_id]),
+            );
+        }
+
+        if !slide.notes.is_empty() {
+            // KN.NoteArchive -> TSWP.StorageArchive com o texto
+            objects.push(
+                IwaObject::new(
+                    slide.note_id,
+                    KN_NOTE_ARCHIVE,
+                    ProtoWriter::new().reference(1, slide.note_storage_id),
+                )
+                .with_refs(&[slide.note_storage_id]),
+            );
+            objects.push(
+                IwaObject::new(
+                    slide.note_storage_id,
+                    TSWP_STORAGE_ARCHIVE,
+                    ProtoWriter::new()
+                        .uint(1, STORAGE_KIND_NOTE)
+                        .reference(2, ID_STYLESHEET)
+                        .string(3, &slide.notes)
+                        .uint(10, 1),
+                )
+                .with_refs(&[ID_STYLESHEET]),
+            );
+        }
+
+        // TSD.ImageArchive na posição do encaixe
+        for image in &slide.images {
+            let (x, y, width, height) = image.frame;
+            let geometry = ProtoWriter::new()
+                .point(1, x, y)
+                .size(2, width, height)
+                .uint(3, 0)
+                .float(4, 0.0);
+            let drawable = ProtoWriter::new()
+                .message(1, geometry)
+                .reference(2, slide.slide_id);
+            objects.push(
+                IwaObject::new(
+                    image.image_id,
+                    TSD_IMAGE_ARCHIVE,
+                    ProtoWriter::new()
+                        .message(1, drawable)
+                        .reference(3, ID_MEDIA_STYLE)
+                        .size(4, image.size.0 as f32, image.size.1 as f32)
+                        .message(11, ProtoWriter::new().uint(1, image.data_id)),
+                )
+                .with_refs(&[slide.slide_id, ID_MEDIA_STYLE])
+                .with_data_refs(&[image.data_id]),
+            );
+        }
+    }
+
+    objects
+}
+
+/// TSP.PackageMetadata: componentes e arquivos de Data/ (com o SHA-1 de cada um)
+fn package_metadata(data_files: &[(&NativeImage, Vec<u8>)], last_id: u64) -> IwaObject {
+    let mut component = ProtoWriter::new()
+        .uint(1, ID_DOCUMENT)
+        .string(2, "Document")
+        .string(3, "Document");
+    for (image, _) in data_files {
+        component = component.message(7, ProtoWriter::new().uint(1, image.data_id));
+    }
+
+    let mut metadata = ProtoWriter::new()
+        .uint(1, last_id)
+        .message(3, component);
+    for (image, digest) in data_files {
+        metadata = metadata.message(
+            4,
+            ProtoWriter::new()
+                .uint(1, image.data_id)
+                .bytes(2, digest)
+                .string(3, &image.data_name)
+                .string(4, &image.data_name),
+        );
+    }
+
+    IwaObject::new(ID_PACKAGE_METADATA, TSP_PACKAGE_METADATA, metadata)
+}
+
+fn thumbnail_jpeg(image: &DynamicImage, width: u32) -> Result<Vec<u8>> {
+    let height = (width as f32 * image.height() as f32 / image.width().max(1) as f32).max(1.0) as u32;
+    let thumb = image.thumbnail(width, height).to_rgb8();
+    let mut bytes = Cursor::new(Vec::new());
+    thumb.write_to(&mut bytes, ImageFormat::Jpeg)?;
+    Ok(bytes.into_inner())
+}
+
+/// UUID (formato v4) derivado do caminho de saída e do horário atual
+fn make_uuid(output_path: &Path) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = Sha1::new();
+    hasher.update(output_path.to_string_lossy().as_bytes());
+    hasher.update(now.to_le_bytes());
+    let mut b = hasher.finalize();
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+
+    let hex: String = b[..16].iter().map(|x| format!("{:02X}", x)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+fn properties_plist(document_uuid: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>documentUUID</key>
+	<string>{uuid}</string>
+	<key>fileFormatVersion</key>
+	<string>13.0</string>
+	<key>isMultiPage</key>
+	<false/>
+	<key>revision</key>
+	<string>0::{uuid}</string>
+	<key>shareUUID</key>
+	<string>{uuid}</string>
+	<key>stableDocumentUUID</key>
+	<string>{uuid}</string>
+	<key>versionUUID</key>
+	<string>{uuid}</string>
+</dict>
+</plist>
+"#,
+        uuid = document_uuid
+    )
+}
+
+const BUILD_VERSION_HISTORY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<array>
+	<string>PDF2Key</string>
+</array>
+</plist>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iwa::tests::{decode_iwa, fields, Value};
+    use crate::layout::FitMode;
+    use std::io::Read;
+
+    fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        archive.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn package_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = |name: &str, width, height| {
+            let path = dir.path().join(name);
+            RgbImage::from_pixel(width, height, Rgb([200, 10, 10])).save(&path).unwrap();
+            path
+        };
+        // As duas primeiras páginas não cobrem o slide 16:9 e ficam sobre o fundo
+        let slides = vec![
+            Slide::new(&page("a.png", 40, 30)).with_notes("primeira"),
+            Slide::new(&page("b.png", 30, 40)),
+            Slide::new(&page("c.png", 32, 18)).with_section("Parte 2"),
+        ];
+        let output = dir.path().join("out.key");
+        let layout = SlideLayout::new(FitMode::Letterbox);
+        let mut written = Vec::new();
+        write_package(&slides, &output, &layout, &mut |done| {
+            written.push(done);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(written, vec![1, 2, 3]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "Data/background-104.png",
+                "Data/slide_0001-107.png",
+                "Data/slide_0002-114.png",
+                "Data/slide_0003-124.png",
+                "Index/Document.iwa",
+                "Index/Metadata.iwa",
+                "Metadata/BuildVersionHistory.plist",
+                "Metadata/DocumentIdentifier",
+                "Metadata/Properties.plist",
+                "preview-micro.jpg",
+                "preview-web.jpg",
+                "preview.jpg",
+            ]
+        );
+
+        let layout: Vec<(u64, u32, Vec<u64>, Vec<u64>)> = decode_iwa(&read_entry(&mut archive, "Index/Document.iwa"))
+            .into_iter()
+            .map(|a| (a.identifier, a.message_type, a.object_references, a.data_references))
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                (ID_DOCUMENT, KN_DOCUMENT_ARCHIVE, vec![ID_SHOW], vec![]),
+                (ID_SHOW, KN_SHOW_ARCHIVE, vec![ID_UI_STATE, ID_THEME, ID_STYLESHEET, 100, 108, 115, 119], vec![]),
+                (ID_UI_STATE, KN_UI_STATE_ARCHIVE, vec![], vec![]),
+                (ID_THEME, KN_THEME_ARCHIVE, vec![ID_STYLESHEET], vec![]),
+                (ID_STYLESHEET, TSS_STYLESHEET_ARCHIVE, vec![ID_MEDIA_STYLE], vec![]),
+                (ID_MEDIA_STYLE, TSD_MEDIA_STYLE_ARCHIVE, vec![], vec![]),
+                // Slide 1: fundo, página e notas
+                (100, KN_SLIDE_NODE_ARCHIVE, vec![101], vec![]),
+                (101, KN_SLIDE_ARCHIVE, vec![ID_STYLESHEET, 105, 106, 102], vec![]),
+                (102, KN_NOTE_ARCHIVE, vec![103], vec![]),
+                (103, TSWP_STORAGE_ARCHIVE, vec![ID_STYLESHEET], vec![]),
+                (105, TSD_IMAGE_ARCHIVE, vec![101, ID_MEDIA_STYLE], vec![104]),
+                (106, TSD_IMAGE_ARCHIVE, vec![101, ID_MEDIA_STYLE], vec![107]),
+                // Slide 2: o mesmo arquivo de fundo
+                (108, KN_SLIDE_NODE_ARCHIVE, vec![109], vec![]),
+                (109, KN_SLIDE_ARCHIVE, vec![ID_STYLESHEET, 112, 113], vec![]),
+                (112, TSD_IMAGE_ARCHIVE, vec![109, ID_MEDIA_STYLE], vec![104]),
+                (113, TSD_IMAGE_ARCHIVE, vec![109, ID_MEDIA_STYLE], vec![114]),
+                // Divisória da seção, pulada, com o título nas notas
+                (115, KN_SLIDE_NODE_ARCHIVE, vec![116], vec![]),
+                (116, KN_SLIDE_ARCHIVE, vec![ID_STYLESHEET, 117], vec![]),
+                (117, KN_NOTE_ARCHIVE, vec![118], vec![]),
+                (118, TSWP_STORAGE_ARCHIVE, vec![ID_STYLESHEET], vec![]),
+                // Slide 3: cobre o slide, sem fundo
+                (119, KN_SLIDE_NODE_ARCHIVE, vec![120], vec![]),
+                (120, KN_SLIDE_ARCHIVE, vec![ID_STYLESHEET, 123], vec![]),
+                (123, TSD_IMAGE_ARCHIVE, vec![120, ID_MEDIA_STYLE], vec![124]),
+            ]
+        );
+
+        // Cada arquivo de Data/ aparece uma vez nos metadados, com o último identificador
+        let metadata = decode_iwa(&read_entry(&mut archive, "Index/Metadata.iwa"));
+        assert_eq!(metadata.len(), 1);
+        let files: Vec<(u64, String)> = fields(&metadata[0].payload)
+            .into_iter()
+            .filter_map(|(field, value)| match (field, value) {
+                (1, Value::Varint(last_id)) => {
+                    assert_eq!(last_id, 125);
+                    None
+                }
+                (4, Value::Bytes(file)) => {
+                    let file = fields(&file);
+                    match (&file[0], &file[2]) {
+                        ((1, Value::Varint(id)), (3, Value::Bytes(name))) => Some((*id, String::from_utf8(name.clone()).unwrap())),
+                        other => panic!("arquivo inesperado: {:?}", other),
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (104, "background-104.png".to_string()),
+                (107, "slide_0001-107.png".to_string()),
+                (114, "slide_0002-114.png".to_string()),
+                (124, "slide_0003-124.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_layers_appear_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = |name: &str| {
+            let path = dir.path().join(name);
+            RgbImage::from_pixel(32, 18, Rgb([200, 10, 10])).save(&path).unwrap();
+            path
+        };
+        let slides = vec![Slide::new(&page("a.png")).with_builds(vec![page("b.png"), page("c.png")])];
+        let output = dir.path().join("out.key");
+        write_package(&slides, &output, &SlideLayout::new(FitMode::Letterbox), &mut |_| Ok(())).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let objects = decode_iwa(&read_entry(&mut archive, "Index/Document.iwa"));
+        let slide = objects.iter().find(|a| a.message_type == KN_SLIDE_ARCHIVE).unwrap();
+        assert_eq!(slide.object_references, vec![ID_STYLESHEET, 108, 111, 104, 106, 109]);
+        // Um chunk por build, na ordem das camadas
+        let chunks: Vec<u64> = fields(&slide.payload)
+            .into_iter()
+            .filter_map(|(field, value)| match (field, value) {
+                (6, Value::Bytes(chunk)) => match fields(&chunk)[0] {
+                    (1, Value::Bytes(ref reference)) => match fields(reference)[0] {
+                        (1, Value::Varint(id)) => Some(id),
+                        ref other => panic!("referência inesperada: {:?}", other),
+                    },
+                    ref other => panic!("chunk inesperado: {:?}", other),
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(chunks, vec![108, 111]);
+
+        let builds: Vec<(u64, Vec<u64>)> = objects
+            .iter()
+            .filter(|a| a.message_type == KN_BUILD_ARCHIVE)
+            .map(|a| (a.identifier, a.object_references.clone()))
+            .collect();
+        assert_eq!(builds, vec![(108, vec![106]), (111, vec![109])]);
+    }
+}
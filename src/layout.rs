@@ -0,0 +1,257 @@
+//! Tamanho dos slides e encaixe da imagem de cada página
+//! Todos os backends usam o mesmo cálculo: a área da imagem em frações do slide, que
+//! cada um converte na sua unidade (EMU, cm ou pontos).
+
+use crate::links::PixelRect;
+
+/// Como a imagem de uma página ocupa o slide
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FitMode {
+    /// Slide com a proporção da primeira página; páginas de outra proporção ficam com
+    /// faixas, como em `Letterbox` (padrão)
+    #[default]
+    MatchPage,
+    /// Página inteira e centralizada no slide de `SlideLayout::size`, com faixas da cor de fundo
+    Letterbox,
+    /// Página cobrindo o slide inteiro, com o excesso cortado
+    Crop,
+    /// Página deformada para ocupar o slide inteiro
+    Stretch,
+}
+
+impl FitMode {
+    /// Interpreta `match`, `letterbox`, `crop` ou `stretch`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "match" | "page" => Some(FitMode::MatchPage),
+            "letterbox" | "fit" => Some(FitMode::Letterbox),
+            "crop" | "fill" => Some(FitMode::Crop),
+            "stretch" => Some(FitMode::Stretch),
+            _ => None,
+        }
+    }
+}
+
+/// Tamanho dos slides, encaixe das páginas e cor das faixas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlideLayout {
+    pub fit: FitMode,
+    /// Tamanho do slide em pontos (o Keynote usa como está; PowerPoint e Impress, só a proporção).
+    /// Com `FitMode::MatchPage` só a largura conta.
+    pub size: (u32, u32),
+    /// Cor RGB do fundo do slide, visível nas faixas
+    pub background: [u8; 3],
+}
+
+impl Default for SlideLayout {
+    fn default() -> Self {
+        Self { fit: FitMode::default(), size: crate::keynote_native::DEFAULT_SLIDE_SIZE, background: [255, 255, 255] }
+    }
+}
+
+impl SlideLayout {
+    pub fn new(fit: FitMode) -> Self {
+        Self { fit, ..Self::default() }
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width.max(1), height.max(1));
+        self
+    }
+
+    pub fn with_background(mut self, background: [u8; 3]) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Proporção do slide (largura / altura) para uma apresentação cuja primeira imagem tem `first` pixels
+    pub fn aspect(&self, first: (u32, u32)) -> f64 {
+        match self.fit {
+            FitMode::MatchPage => first.0.max(1) as f64 / first.1.max(1) as f64,
+            _ => self.size.0.max(1) as f64 / self.size.1.max(1) as f64,
+        }
+    }
+
+    /// Tamanho do slide do Keynote em pontos
+    pub fn slide_size(&self, first: (u32, u32)) -> (u32, u32) {
+        match self.fit {
+            FitMode::MatchPage => (self.size.0, (self.size.0 as f64 / self.aspect(first)).round().max(1.0) as u32),
+            _ => self.size,
+        }
+    }
+
+    /// Área da imagem de `image` pixels num slide com proporção `aspect`
+    pub fn place(&self, aspect: f64, image: (u32, u32)) -> Placement {
+        let image_aspect = image.0.max(1) as f64 / image.1.max(1) as f64;
+        let wider = image_aspect > aspect;
+        let (width, height) = match self.fit {
+            FitMode::Stretch => (1.0, 1.0),
+            FitMode::MatchPage | FitMode::Letterbox if wider => (1.0, aspect / image_aspect),
+            FitMode::MatchPage | FitMode::Letterbox => (image_aspect / aspect, 1.0),
+            FitMode::Crop if wider => (image_aspect / aspect, 1.0),
+            FitMode::Crop => (1.0, aspect / image_aspect),
+        };
+        Placement { x: (1.0 - width) / 2.0, y: (1.0 - height) / 2.0, width, height }
+    }
+}
+
+/// Área da imagem da página no slide, em frações do slide
+///
+/// Com `FitMode::Crop` a área passa das bordas do slide (`x` ou `y` negativos).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Tolerância para considerar que a imagem encosta nas bordas do slide
+const EDGE_TOLERANCE: f64 = 1e-3;
+
+impl Placement {
+    /// A imagem cobre o slide inteiro: nenhuma faixa de fundo aparece
+    pub fn covers(&self) -> bool {
+        self.x <= EDGE_TOLERANCE && self.y <= EDGE_TOLERANCE
+            && self.x + self.width >= 1.0 - EDGE_TOLERANCE
+            && self.y + self.height >= 1.0 - EDGE_TOLERANCE
+    }
+
+    /// Frações da imagem fora do slide em cada lado: (esquerda, topo, direita, base)
+    pub fn cropped(&self) -> (f64, f64, f64, f64) {
+        let outside = |before: f64, size: f64| (-before / size).max(0.0);
+        let (left, top) = (outside(self.x, self.width), outside(self.y, self.height));
+        (left, top, outside(1.0 - self.x - self.width, self.width), outside(1.0 - self.y - self.height, self.height))
+    }
+
+    /// Converte pixels da imagem (`image` pixels) em unidades de um slide de `slide` unidades
+    pub(crate) fn mapping(&self, slide: (f64, f64), image: (u32, u32)) -> PixelMapping {
+        PixelMapping {
+            origin: (self.x * slide.0, self.y * slide.1),
+            scale: (
+                self.width * slide.0 / image.0.max(1) as f64,
+                self.height * slide.1 / image.1.max(1) as f64,
+            ),
+        }
+    }
+}
+
+/// Pixels da imagem da página para a unidade do slide de um backend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PixelMapping {
+    pub origin: (f64, f64),
+    /// Unidades por pixel nos dois eixos
+    pub scale: (f64, f64),
+}
+
+impl PixelMapping {
+    /// Retângulo em pixels convertido: (x, y, largura, altura)
+    pub(crate) fn rect(&self, rect: &PixelRect) -> (f64, f64, f64, f64) {
+        (
+            self.origin.0 + rect.x as f64 * self.scale.0,
+            self.origin.1 + rect.y as f64 * self.scale.1,
+            rect.width as f64 * self.scale.0,
+            rect.height as f64 * self.scale.1,
+        )
+    }
+
+    /// A imagem inteira, de `image` pixels
+    pub(crate) fn image(&self, image: (u32, u32)) -> (f64, f64, f64, f64) {
+        self.rect(&PixelRect { x: 0, y: 0, width: image.0, height: image.1 })
+    }
+}
+
+/// Tamanho de slide em pontos: `16:9` (1920×1080), `4:3` (1024×768) ou `<largura>x<altura>`
+pub fn parse_slide_size(text: &str) -> Option<(u32, u32)> {
+    let text = text.trim().to_ascii_lowercase().replace('×', "x");
+    match text.as_str() {
+        "16:9" => Some((1920, 1080)),
+        "4:3" => Some((1024, 768)),
+        _ => {
+            let (width, height) = text.split_once('x')?;
+            let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+            (width > 0 && height > 0).then_some((width, height))
+        }
+    }
+}
+
+/// Cor `#RRGGBB` (ou `RRGGBB`) em RGB
+pub fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(fit: FitMode, image: (u32, u32)) -> Placement {
+        SlideLayout::new(fit).place(2.0, image)
+    }
+
+    #[test]
+    fn slide_sizes_and_colors() {
+        assert_eq!(parse_slide_size("16:9"), Some((1920, 1080)));
+        assert_eq!(parse_slide_size(" 4:3 "), Some((1024, 768)));
+        assert_eq!(parse_slide_size("1280x720"), Some((1280, 720)));
+        assert_eq!(parse_slide_size("800×600"), Some((800, 600)));
+        assert_eq!(parse_slide_size("0x600"), None);
+        assert_eq!(parse_slide_size("grande"), None);
+
+        assert_eq!(parse_color("#1a2B3c"), Some([0x1a, 0x2b, 0x3c]));
+        assert_eq!(parse_color("ffffff"), Some([255, 255, 255]));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("zzzzzz"), None);
+        assert_eq!(parse_color("ééé"), None);
+    }
+
+    #[test]
+    fn aspect_follows_fit() {
+        let layout = SlideLayout::new(FitMode::MatchPage).with_size(1600, 900);
+        assert_eq!(layout.aspect((400, 300)), 4.0 / 3.0);
+        assert_eq!(layout.slide_size((400, 300)), (1600, 1200));
+        let layout = SlideLayout::new(FitMode::Letterbox).with_size(1600, 900);
+        assert_eq!(layout.aspect((400, 300)), 16.0 / 9.0);
+        assert_eq!(layout.slide_size((400, 300)), (1600, 900));
+    }
+
+    #[test]
+    fn placement_per_fit() {
+        // Página quadrada num slide 2:1
+        for fit in [FitMode::MatchPage, FitMode::Letterbox] {
+            let placement = place(fit, (300, 300));
+            assert_eq!(placement, Placement { x: 0.25, y: 0.0, width: 0.5, height: 1.0 });
+            assert!(!placement.covers());
+            assert_eq!(placement.cropped(), (0.0, 0.0, 0.0, 0.0));
+        }
+        let placement = place(FitMode::Crop, (300, 300));
+        assert_eq!(placement, Placement { x: 0.0, y: -0.5, width: 1.0, height: 2.0 });
+        assert!(placement.covers());
+        assert_eq!(placement.cropped(), (0.0, 0.25, 0.0, 0.25));
+        let placement = place(FitMode::Stretch, (300, 300));
+        assert_eq!(placement, Placement { x: 0.0, y: 0.0, width: 1.0, height: 1.0 });
+        assert!(placement.covers());
+
+        // Página 4:1, mais larga que o slide
+        assert_eq!(place(FitMode::Letterbox, (400, 100)), Placement { x: 0.0, y: 0.25, width: 1.0, height: 0.5 });
+        let placement = place(FitMode::Crop, (400, 100));
+        assert_eq!(placement, Placement { x: -0.5, y: 0.0, width: 2.0, height: 1.0 });
+        assert_eq!(placement.cropped(), (0.25, 0.0, 0.25, 0.0));
+    }
+
+    #[test]
+    fn mapping_to_slide_units() {
+        let mapping = place(FitMode::Letterbox, (400, 400)).mapping((800.0, 400.0), (400, 400));
+        assert_eq!(mapping, PixelMapping { origin: (200.0, 0.0), scale: (1.0, 1.0) });
+        assert_eq!(mapping.rect(&PixelRect { x: 100, y: 40, width: 200, height: 100 }), (300.0, 40.0, 200.0, 100.0));
+        assert_eq!(mapping.image((400, 400)), (200.0, 0.0, 400.0, 400.0));
+
+        // Cortada, a origem fica fora do slide
+        let mapping = place(FitMode::Crop, (400, 400)).mapping((800.0, 400.0), (400, 400));
+        assert_eq!(mapping, PixelMapping { origin: (0.0, -200.0), scale: (2.0, 2.0) });
+    }
+}
@@ -0,0 +1,42 @@
+//! PDF2Key - Conversão de PDF em apresentações (Keynote, PowerPoint, Impress)
+//!
+//! Biblioteca usada pela aplicação desktop e pela linha de comando. Para embutir a
+//! conversão em outra ferramenta, monte um [`ConversionRequest`] e chame [`convert`].
+
+pub mod conversion;
+pub mod editable;
+pub mod encoding;
+pub mod error;
+pub mod handout;
+pub mod images;
+pub mod keynote;
+pub mod layout;
+pub mod links;
+pub mod notes;
+pub mod odp;
+pub mod outline;
+pub mod overlays;
+pub mod page_selection;
+pub mod pdf_processor;
+pub mod pipeline;
+pub mod pptx;
+pub mod slide;
+pub mod trim;
+pub mod vector;
+
+mod iwa;
+mod keynote_native;
+mod xml;
+
+pub use conversion::{convert, CancellationToken, ConversionRequest, ConversionWarning, OutputFormat, PdfInput, ProgressEvent, ProgressSink};
+pub use encoding::ImageEncoding;
+pub use error::Pdf2KeyError;
+pub use handout::{Handout, PageOrder};
+pub use layout::{FitMode, SlideLayout};
+pub use notes::NotesSource;
+pub use outline::OutlineEntry;
+pub use overlays::OverlayMode;
+pub use page_selection::PageSelection;
+pub use pdf_processor::{BeamerNotes, Password, Resolution};
+pub use slide::Slide;
+pub use trim::{Trim, TrimSource};
@@ -0,0 +1,61 @@
+//! Links das páginas do PDF (URIs e destinos internos)
+//! Viram áreas clicáveis transparentes sobre a imagem do slide
+
+/// Retângulo em pixels da imagem renderizada, com origem no canto superior esquerdo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Destino de um link no PDF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// Endereço externo (web, e-mail)
+    Uri(String),
+    /// Página do mesmo documento (índice 0-based)
+    Page(usize),
+}
+
+/// Link de uma página, na escala da imagem renderizada
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageLink {
+    pub rect: PixelRect,
+    pub target: LinkTarget,
+}
+
+/// Destino de um link na apresentação
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlideLinkTarget {
+    Url(String),
+    /// Slide da apresentação (índice 0-based entre os slides adicionados ao builder)
+    Slide(usize),
+}
+
+/// Área clicável de um slide, em pixels da imagem do slide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlideLink {
+    pub rect: PixelRect,
+    pub target: SlideLinkTarget,
+}
+
+/// Converte os links de uma página para a apresentação
+///
+/// `slide_pages` traz a primeira e a última página do documento mostradas por cada
+/// slide. Links para páginas que não viraram slide são descartados.
+pub(crate) fn resolve(links: Vec<PageLink>, slide_pages: &[(usize, usize)]) -> Vec<SlideLink> {
+    links
+        .into_iter()
+        .filter_map(|link| {
+            let target = match link.target {
+                LinkTarget::Uri(uri) => SlideLinkTarget::Url(uri),
+                LinkTarget::Page(page) => {
+                    SlideLinkTarget::Slide(slide_pages.iter().position(|&(first, last)| first <= page && page <= last)?)
+                }
+            };
+            Some(SlideLink { rect: link.rect, target })
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+//! Notas do apresentador: texto extraído do PDF ou lido de um arquivo à parte
+
+use crate::error::Pdf2KeyError;
+use crate::pdf_processor::PdfDocumentHandle;
+use std::path::{Path, PathBuf};
+
+/// Origem das notas do apresentador de cada slide
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NotesSource {
+    /// Slides sem notas
+    #[default]
+    None,
+    /// Todo o texto da página do PDF (em páginas do Beamer divididas, o da metade de notas)
+    PageText,
+    /// Arquivo de texto com as notas de cada página, separadas por linhas `---`
+    ///
+    /// O N-ésimo bloco pertence à página N do PDF, independente da seleção de páginas; as
+    /// células de uma apostila dividida levam o bloco da sua página.
+    Sidecar(PathBuf),
+}
+
+/// Notas de cada página selecionada (`page_indices`, 0-based), na mesma ordem
+pub(crate) fn collect(
+    source: &NotesSource,
+    document: &PdfDocumentHandle,
+    page_indices: &[usize],
+) -> Result<Vec<String>, Pdf2KeyError> {
+    match source {
+        NotesSource::None => Ok(vec![String::new(); page_indices.len()]),
+        NotesSource::PageText => page_indices
+            .iter()
+            .map(|&index| {
+                // Páginas do Beamer divididas: só o texto da metade de notas
+                let text = match document.notes_text(index)? {
+                    Some(text) => text,
+                    None => document.page_text(index)?,
+                };
+                Ok(normalize(&text))
+            })
+            .collect(),
+        NotesSource::Sidecar(path) => {
+            let pages: Vec<usize> = page_indices.iter().map(|&index| document.source_page(index)).collect();
+            Ok(sidecar_notes(&read_sidecar(path)?, &pages))
+        }
+    }
+}
+
+/// Bloco de cada página (0-based) em `pages`; páginas além do arquivo ficam sem notas
+fn sidecar_notes(blocks: &[String], pages: &[usize]) -> Vec<String> {
+    pages.iter().map(|&page| blocks.get(page).cloned().unwrap_or_default()).collect()
+}
+
+/// Lê um arquivo de notas: um bloco por página, separados por linhas contendo apenas `---`
+fn read_sidecar(path: &Path) -> Result<Vec<String>, Pdf2KeyError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|source| Pdf2KeyError::Io { path: path.to_path_buf(), source })?;
+
+    let mut blocks = vec![String::new()];
+    for line in normalize(&content).lines() {
+        if line.trim() == "---" {
+            blocks.push(String::new());
+        } else {
+            let block = blocks.last_mut().unwrap();
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+
+    Ok(blocks.iter().map(|b| b.trim().to_string()).collect())
+}
+
+/// Normaliza quebras de linha (o PDFium usa `\r\n`) e remove espaços nas pontas
+fn normalize(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page_selection::PageSelection;
+
+    #[test]
+    fn sidecar_blocks_follow_pdf_pages() {
+        let path = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(path.path(), "um\r\n---\ndois\n---\n\n---\nquatro\n").unwrap();
+        let blocks = read_sidecar(path.path()).unwrap();
+        assert_eq!(blocks, ["um", "dois", "", "quatro"]);
+
+        // Páginas 2 e 4 a 5: o bloco de cada página, não o da posição na seleção
+        let pages = PageSelection::parse("2,4-5").unwrap().resolve(5).unwrap();
+        assert_eq!(sidecar_notes(&blocks, &pages), ["dois", "quatro", ""]);
+        // Apostila dividida: as células da página 2 repetem o bloco dela
+        assert_eq!(sidecar_notes(&blocks, &[1, 1, 3]), ["dois", "dois", "quatro"]);
+    }
+}
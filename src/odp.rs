@@ -0,0 +1,401 @@
+//! Módulo para geração de apresentações OpenDocument (.odp)
+//! Escreve o pacote ODF diretamente, compatível com o LibreOffice Impress
+
+use crate::editable::{SlideObject, TextBox};
+use crate::layout::{PixelMapping, SlideLayout};
+use crate::links::{PixelRect, SlideLink, SlideLinkTarget};
+use crate::slide::Slide;
+use crate::xml;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.presentation";
+
+/// Tamanho padrão da página (em pontos) quando não informado: 16:9
+const DEFAULT_PAGE_SIZE: (f32, f32) = (960.0, 540.0);
+
+/// Cria apresentações ODP com uma imagem por slide
+pub struct OdpBuilder {
+    slides: Vec<Slide>,
+    page_size: (f32, f32),
+    layout: SlideLayout,
+}
+
+impl Default for OdpBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OdpBuilder {
+    /// Cria um novo builder para apresentações ODP
+    pub fn new() -> Self {
+        Self {
+            slides: Vec::new(),
+            page_size: DEFAULT_PAGE_SIZE,
+            layout: SlideLayout::default(),
+        }
+    }
+
+    /// Define o tamanho da página em pontos (1/72"), normalmente o da página do PDF
+    pub fn with_page_size(mut self, width: f32, height: f32) -> Self {
+        self.page_size = (width, height);
+        self
+    }
+
+    /// Proporção dos slides, encaixe das páginas e cor de fundo
+    ///
+    /// A largura da página continua a de `with_page_size`; a altura segue a proporção do slide.
+    /// Com `FitMode::Crop` a imagem passa das bordas da página, que a cortam na apresentação.
+    pub fn with_layout(mut self, layout: SlideLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Adiciona uma imagem como um novo slide
+    pub fn add_slide(&mut self, image_path: &Path) {
+        self.push_slide(Slide::new(image_path));
+    }
+
+    /// Adiciona um slide com notas e camadas de build (cada camada aparece com um clique)
+    pub fn push_slide(&mut self, slide: Slide) {
+        self.slides.push(slide);
+    }
+
+    /// Constrói e salva o arquivo .odp
+    pub fn build(&self, output_path: &Path) -> Result<()> {
+        self.build_with_progress(output_path, &mut |_| Ok(()))
+    }
+
+    /// Constrói e salva o arquivo .odp, chamando `on_slide` com o número de slides já gravados
+    ///
+    /// Um erro retornado por `on_slide` interrompe a geração (ex: cancelamento).
+    pub fn build_with_progress(&self, output_path: &Path, on_slide: &mut dyn FnMut(usize) -> Result<()>) -> Result<()> {
+        if self.slides.is_empty() {
+            anyhow::bail!("Nenhum slide foi adicionado");
+        }
+
+        let file = File::create(output_path).context("Falha ao criar o arquivo .odp")?;
+        let mut zip = ZipWriter::new(file);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+        // O mimetype precisa ser a primeira entrada, sem compressão
+        zip.start_file("mimetype", stored)?;
+        zip.write_all(MIMETYPE.as_bytes())?;
+
+        // Nome dentro do pacote e arquivo de origem de cada imagem; slide i usa pictures[i][0],
+        // para cada passo de build pictures[i][k], em seguida as imagens dos objetos editáveis e,
+        // por último, a versão SVG do slide
+        let pictures: Vec<Vec<(String, &Path)>> = self.slides
+            .iter()
+            .enumerate()
+            .map(|(i, slide)| {
+                let main = (format!("Pictures/slide_{:04}.{}", i + 1, image_extension(&slide.image)), slide.image.as_path());
+                let layers = slide.builds.iter().enumerate().map(|(k, layer)| {
+                    (format!("Pictures/slide_{:04}_build_{:02}.png", i + 1, k + 1), layer.as_path())
+                });
+                let objects = slide.objects.iter().enumerate().filter_map(|(k, object)| match object {
+                    SlideObject::Image(placed) => {
+                        let name = format!("Pictures/slide_{:04}_obj_{:02}.{}", i + 1, k + 1, image_extension(&placed.path));
+                        Some((name, placed.path.as_path()))
+                    }
+                    SlideObject::Text(_) => None,
+                });
+                let vector = slide.vector.iter().map(|vector| (format!("Pictures/slide_{:04}.svg", i + 1), vector.as_path()));
+                std::iter::once(main).chain(layers).chain(objects).chain(vector).collect()
+            })
+            .collect();
+
+        for (i, slide_pictures) in pictures.iter().enumerate() {
+            for (name, path) in slide_pictures {
+                zip.start_file(name.as_str(), stored)?;
+                zip.write_all(&std::fs::read(path)?)?;
+            }
+            on_slide(i + 1)?;
+        }
+
+        // Com `FitMode::MatchPage` a página segue a proporção da primeira imagem
+        let first = image::image_dimensions(&self.slides[0].image)
+            .context("Falha ao ler imagem do primeiro slide")?;
+        let aspect = self.layout.aspect(first) as f32;
+        let page_size = (self.page_size.0, self.page_size.0 / aspect.max(f32::EPSILON));
+
+        zip.start_file("content.xml", deflated)?;
+        zip.write_all(self.content(&pictures, page_size)?.as_bytes())?;
+
+        zip.start_file("styles.xml", deflated)?;
+        zip.write_all(self.styles(page_size).as_bytes())?;
+
+        zip.start_file("meta.xml", deflated)?;
+        zip.write_all(META.as_bytes())?;
+
+        zip.start_file("META-INF/manifest.xml", deflated)?;
+        zip.write_all(manifest(pictures.iter().flatten()).as_bytes())?;
+
+        zip.finish()?;
+
+        log::info!("Apresentação Impress criada: {}", output_path.display());
+        Ok(())
+    }
+
+    fn content(&self, pictures: &[Vec<(String, &Path)>], page_size: (f32, f32)) -> Result<String> {
+        let (width, height) = (to_cm(page_size.0), to_cm(page_size.1));
+        let aspect = (page_size.0 / page_size.1) as f64;
+
+        // Nomes de todas as páginas antes de escrevê-las: os links apontam para eles.
+        // Divisórias de seção também são páginas, então os números contam as duas.
+        let mut names = PageNames::default();
+        let mut page_number = 0;
+        let mut layout = Vec::with_capacity(self.slides.len());
+        for slide in &self.slides {
+            let divider = slide.section.as_ref().map(|section| {
+                page_number += 1;
+                names.unique(section, page_number)
+            });
+            page_number += 1;
+            layout.push((divider, page_number, names.unique(&slide.title, page_number)));
+        }
+
+        let mut text_styles = TextStyles::default();
+        let mut pages = String::new();
+        for (i, ((slide_pictures, slide), (divider, page_number, name))) in
+            pictures.iter().zip(&self.slides).zip(&layout).enumerate()
+        {
+            let n = i + 1;
+            if let (Some(divider), Some(section)) = (divider, &slide.section) {
+                pages.push_str(&format!(
+                    r#"<draw:page draw:name="{divider}" draw:style-name="dp2" draw:master-page-name="Default"><draw:frame draw:style-name="gr2" svg:width="{width}" svg:height="{height}" svg:x="0cm" svg:y="0cm"><draw:text-box><text:p text:style-name="P1">{title}</text:p></draw:text-box></draw:frame></draw:page>"#,
+                    title = xml::escape(section)
+                ));
+            }
+            let (slide_pictures, object_pictures) = slide_pictures.split_at(1 + slide.builds.len());
+            let (object_pictures, vector) = object_pictures.split_at(object_pictures.len() - slide.vector.iter().count());
+            // O Impress usa a primeira imagem do frame que conseguir abrir: o SVG, depois a imagem
+            let vector = match vector.first() {
+                Some((href, _)) => format!(r#"<draw:image xlink:href="{href}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/>"#),
+                None => String::new(),
+            };
+            // Imagem da página e camadas na área do encaixe; objetos e links na escala dela
+            let size = image::image_dimensions(&slide.image)
+                .context(format!("Falha ao ler imagem do slide {}", n))?;
+            let mapping = self.layout.place(aspect, size).mapping((page_size.0 as f64, page_size.1 as f64), size);
+            let (x, y, w, h) = frame_rect(&PixelRect { x: 0, y: 0, width: size.0, height: size.1 }, &mapping);
+            let mut frames: String = slide_pictures
+                .iter()
+                .enumerate()
+                .map(|(k, (href, _))| {
+                    // Camadas de build ganham um id para a animação de entrada
+                    let id = if k == 0 { String::new() } else { format!(r#" draw:id="build{n}_{k}" xml:id="build{n}_{k}""#) };
+                    let vector = if k == 0 { vector.as_str() } else { "" };
+                    format!(
+                        r#"<draw:frame draw:style-name="gr1"{id} svg:width="{w}" svg:height="{h}" svg:x="{x}" svg:y="{y}">{vector}<draw:image xlink:href="{href}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/></draw:frame>"#
+                    )
+                })
+                .collect();
+
+            // Objetos editáveis e links por cima das imagens
+            let mut links = String::new();
+            if !slide.links.is_empty() || !slide.objects.is_empty() {
+                let mut object_pictures = object_pictures.iter();
+                for object in &slide.objects {
+                    match object {
+                        SlideObject::Image(placed) => {
+                            let Some((href, _)) = object_pictures.next() else { continue };
+                            let (x, y, w, h) = frame_rect(&placed.rect, &mapping);
+                            frames.push_str(&format!(
+                                r#"<draw:frame draw:style-name="gr1" svg:width="{w}" svg:height="{h}" svg:x="{x}" svg:y="{y}"><draw:image xlink:href="{href}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/></draw:frame>"#
+                            ));
+                        }
+                        SlideObject::Text(text) => frames.push_str(&text_frame(text, &text_styles.name(text, mapping.scale.1 as f32), &mapping)),
+                    }
+                }
+                for link in &slide.links {
+                    let href = match &link.target {
+                        SlideLinkTarget::Url(url) => xml::escape(url),
+                        SlideLinkTarget::Slide(target) => format!("#{}", layout[*target].2),
+                    };
+                    links.push_str(&link_shape(link, &href, &mapping));
+                }
+            }
+
+            pages.push_str(&format!(
+                r#"<draw:page draw:name="{name}" draw:style-name="dp1" draw:master-page-name="Default">{frames}{links}{timing}{notes}</draw:page>"#,
+                name = name,
+                frames = frames,
+                links = links,
+                timing = build_timing(n, slide.builds.len()),
+                notes = presentation_notes(*page_number, &slide.notes)
+            ));
+        }
+        Ok(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content {ns} office:version="1.3"><office:automatic-styles><style:style style:name="dp1" style:family="drawing-page"><style:drawing-page-properties presentation:background-visible="true" presentation:background-objects-visible="true"/></style:style><style:style style:name="dp2" style:family="drawing-page"><style:drawing-page-properties presentation:visibility="hidden" presentation:background-visible="true" presentation:background-objects-visible="true"/></style:style><style:style style:name="gr1" style:family="graphic"><style:graphic-properties draw:stroke="none" draw:fill="none"/></style:style><style:style style:name="gr3" style:family="graphic"><style:graphic-properties draw:stroke="none" draw:fill="none"/></style:style><style:style style:name="gr2" style:family="graphic"><style:graphic-properties draw:stroke="none" draw:fill="none" draw:textarea-horizontal-align="center" draw:textarea-vertical-align="middle"/></style:style><style:style style:name="gr4" style:family="graphic"><style:graphic-properties draw:stroke="none" draw:fill="none" draw:auto-grow-width="true" draw:auto-grow-height="true" fo:wrap-option="no-wrap" fo:padding-top="0cm" fo:padding-bottom="0cm" fo:padding-left="0cm" fo:padding-right="0cm"/></style:style><style:style style:name="P1" style:family="paragraph"><style:paragraph-properties fo:text-align="center"/><style:text-properties fo:font-size="40pt" fo:font-weight="bold"/></style:style>{text_styles}</office:automatic-styles><office:body><office:presentation>{pages}</office:presentation></office:body></office:document-content>"#,
+            ns = NAMESPACES,
+            text_styles = text_styles.to_xml(),
+            pages = pages
+        ))
+    }
+
+    fn styles(&self, (width, height): (f32, f32)) -> String {
+        let [r, g, b] = self.layout.background;
+        let orientation = if width >= height { "landscape" } else { "portrait" };
+        format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-styles {ns} office:version="1.3"><office:styles/><office:automatic-styles><style:page-layout style:name="PM1"><style:page-layout-properties fo:margin-top="0cm" fo:margin-bottom="0cm" fo:margin-left="0cm" fo:margin-right="0cm" fo:page-width="{w}" fo:page-height="{h}" style:print-orientation="{orientation}"/></style:page-layout><style:style style:name="Mdp1" style:family="drawing-page"><style:drawing-page-properties draw:fill="solid" draw:fill-color="#{r:02x}{g:02x}{b:02x}"/></style:style></office:automatic-styles><office:master-styles><style:master-page style:name="Default" style:page-layout-name="PM1" draw:style-name="Mdp1"/></office:master-styles></office:document-styles>"##,
+            ns = NAMESPACES,
+            w = to_cm(width),
+            h = to_cm(height),
+            orientation = orientation
+        )
+    }
+}
+
+/// Nomes das páginas, que o Impress exige únicos
+#[derive(Default)]
+struct PageNames(std::collections::HashSet<String>);
+
+impl PageNames {
+    /// `title` (escapado) ou, se vazio, "pageN"; repetições ganham o número da página
+    fn unique(&mut self, title: &str, page_number: usize) -> String {
+        let name = if title.is_empty() { format!("page{}", page_number) } else { title.to_string() };
+        let name = if self.0.contains(&name) { format!("{} ({})", name, page_number) } else { name };
+        self.0.insert(name.clone());
+        xml::escape(&name)
+    }
+}
+
+/// Estilos automáticos de texto ("T1", "T2", ...), um por combinação de fonte, tamanho e cor
+#[derive(Default)]
+struct TextStyles(Vec<String>);
+
+impl TextStyles {
+    /// Nome do estilo de `text`; `points` converte pixels da imagem do slide em pontos
+    fn name(&mut self, text: &TextBox, points: f32) -> String {
+        let [r, g, b] = text.color;
+        let properties = format!(
+            r##"<style:text-properties fo:font-family="{font}" fo:font-size="{size:.1}pt" fo:color="#{r:02x}{g:02x}{b:02x}" fo:font-weight="{weight}" fo:font-style="{style}"/>"##,
+            font = xml::escape(&text.font),
+            size = text.size * points,
+            weight = if text.bold { "bold" } else { "normal" },
+            style = if text.italic { "italic" } else { "normal" }
+        );
+        let index = self.0.iter().position(|p| *p == properties).unwrap_or_else(|| {
+            self.0.push(properties);
+            self.0.len() - 1
+        });
+        format!("T{}", index + 1)
+    }
+
+    fn to_xml(&self) -> String {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, properties)| format!(r#"<style:style style:name="T{}" style:family="text">{}</style:style>"#, i + 1, properties))
+            .collect()
+    }
+}
+
+/// Página de notas (A4 retrato): miniatura do slide em cima e o texto das notas embaixo
+fn presentation_notes(page_number: usize, notes: &str) -> String {
+    if notes.is_empty() {
+        return String::new();
+    }
+    let paragraphs: String = notes
+        .lines()
+        .map(|line| format!("<text:p>{}</text:p>", xml::escape(line.trim_end())))
+        .collect();
+    format!(
+        r#"<presentation:notes draw:style-name="dp1"><draw:page-thumbnail presentation:class="page" svg:width="14.8cm" svg:height="11.1cm" svg:x="3.1cm" svg:y="2.2cm" draw:page-number="{page_number}"/><draw:frame presentation:class="notes" svg:width="16.8cm" svg:height="13.4cm" svg:x="2.1cm" svg:y="14.1cm"><draw:text-box>{paragraphs}</draw:text-box></draw:frame></presentation:notes>"#,
+        page_number = page_number,
+        paragraphs = paragraphs
+    )
+}
+
+/// Retângulo transparente que abre `href` (URL ou "#página") ao ser clicado
+///
+/// `mapping` converte pixels da imagem do slide em pontos.
+fn link_shape(link: &SlideLink, href: &str, mapping: &PixelMapping) -> String {
+    let (x, y, w, h) = frame_rect(&link.rect, mapping);
+    format!(
+        r#"<draw:rect draw:style-name="gr3" svg:x="{x}" svg:y="{y}" svg:width="{w}" svg:height="{h}"><office:event-listeners><presentation:event-listener script:event-name="dom:click" presentation:action="show" xlink:href="{href}" xlink:type="simple" xlink:show="embed" xlink:actuate="onRequest"/></office:event-listeners></draw:rect>"#
+    )
+}
+
+/// Caixa de texto de uma linha, sem margens, na posição do texto no PDF
+fn text_frame(text: &TextBox, style: &str, mapping: &PixelMapping) -> String {
+    let (x, y, w, h) = frame_rect(&text.rect, mapping);
+    format!(
+        r#"<draw:frame draw:style-name="gr4" svg:width="{w}" svg:height="{h}" svg:x="{x}" svg:y="{y}"><draw:text-box><text:p><text:span text:style-name="{style}">{text}</text:span></text:p></draw:text-box></draw:frame>"#,
+        text = xml::escape(&text.text)
+    )
+}
+
+/// Retângulo em pixels da imagem do slide em centímetros: (x, y, largura, altura)
+fn frame_rect(rect: &PixelRect, mapping: &PixelMapping) -> (String, String, String, String) {
+    let (x, y, w, h) = mapping.rect(rect);
+    (to_cm(x as f32), to_cm(y as f32), to_cm(w as f32), to_cm(h as f32))
+}
+
+/// Animações de entrada ("Aparecer", ao clicar) para as camadas de build do slide `n`
+fn build_timing(n: usize, builds: usize) -> String {
+    if builds == 0 {
+        return String::new();
+    }
+    let clicks: String = (1..=builds)
+        .map(|k| {
+            format!(
+                r#"<anim:par smil:begin="next"><anim:par smil:begin="0s"><anim:par smil:begin="0s" smil:fill="hold" presentation:node-type="on-click" presentation:preset-class="entrance" presentation:preset-id="ooo-entrance-appear"><anim:set smil:begin="0s" smil:dur="0.001s" smil:fill="hold" smil:targetElement="build{n}_{k}" smil:attributeName="visibility" smil:to="visible"/></anim:par></anim:par></anim:par>"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<anim:par presentation:node-type="timing-root"><anim:seq presentation:node-type="main-sequence">{}</anim:seq></anim:par>"#,
+        clicks
+    )
+}
+
+/// Converte pontos (1/72") para centímetros no formato ODF
+fn to_cm(points: f32) -> String {
+    format!("{:.3}cm", points / 72.0 * 2.54)
+}
+
+fn image_extension(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+        Some(e) if e == "jpg" || e == "jpeg" => "jpg",
+        Some(e) if e == "svg" => "svg",
+        Some(e) if e == "webp" => "webp",
+        _ => "png",
+    }
+}
+
+fn manifest<'a>(pictures: impl Iterator<Item = &'a (String, &'a Path)>) -> String {
+    let mut entries = String::new();
+    for (picture, source) in pictures {
+        let media_type = match image_extension(source) {
+            "jpg" => "image/jpeg",
+            "svg" => "image/svg+xml",
+            "webp" => "image/webp",
+            _ => "image/png",
+        };
+        entries.push_str(&format!(
+            r#"<manifest:file-entry manifest:full-path="{}" manifest:media-type="{}"/>"#,
+            picture, media_type
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.3"><manifest:file-entry manifest:full-path="/" manifest:version="1.3" manifest:media-type="{mimetype}"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/><manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/><manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>{entries}</manifest:manifest>"#,
+        mimetype = MIMETYPE,
+        entries = entries
+    )
+}
+
+const NAMESPACES: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0" xmlns:anim="urn:oasis:names:tc:opendocument:xmlns:animation:1.0" xmlns:smil="urn:oasis:names:tc:opendocument:xmlns:smil-compatible:1.0" xmlns:script="urn:oasis:names:tc:opendocument:xmlns:script:1.0""#;
+
+const META: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" office:version="1.3"><office:meta><meta:generator>PDF2Key</meta:generator></office:meta></office:document-meta>"#;
@@ -0,0 +1,120 @@
+//! Sumário (bookmarks) e rótulos de página do PDF
+//! Viram nomes de slides e seções, para navegar em apresentações longas
+
+use crate::slide::Slide;
+
+/// Entrada do sumário do PDF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub title: String,
+    /// Página de destino (índice 0-based no documento)
+    pub page: usize,
+    /// Profundidade no sumário (0 = nível superior)
+    pub level: usize,
+}
+
+/// Dá nome e seção a cada slide
+///
+/// `pages` traz, para cada slide, a primeira e a última página do documento que ele
+/// mostra; `labels`, o rótulo da primeira. O nome vem da entrada do sumário que aponta
+/// para as páginas do slide (ou para páginas omitidas logo antes dele); sem entrada,
+/// do rótulo da página, se diferente do número. As entradas de nível superior viram seções.
+///
+/// Os slides não precisam seguir a ordem das páginas (ex: seleção `5,1-3`): os nomes são
+/// resolvidos na ordem das páginas, e as seções começam onde a seção muda na ordem dos slides.
+pub(crate) fn apply(slides: &mut [Slide], pages: &[(usize, usize)], outline: &[OutlineEntry], labels: &[Option<String>]) {
+    let count = slides.len().min(pages.len()).min(labels.len());
+    let mut by_page: Vec<usize> = (0..count).collect();
+    by_page.sort_by_key(|&i| pages[i]);
+
+    let mut previous_last = None;
+    for i in by_page {
+        let (first, last) = pages[i];
+        let in_slide = |entry: &&OutlineEntry| previous_last.is_none_or(|p| entry.page > p) && entry.page <= last;
+
+        // Entrada mais próxima da página do slide; na mesma página, a mais profunda
+        let title = outline
+            .iter()
+            .filter(in_slide)
+            .max_by_key(|entry| (entry.page, entry.level))
+            .map(|entry| entry.title.clone())
+            .or_else(|| labels[i].clone().filter(|l| !l.is_empty() && *l != (first + 1).to_string()));
+        if let Some(title) = title {
+            slides[i].title = title;
+        }
+        previous_last = Some(last);
+    }
+
+    let mut current_section = None;
+    for (slide, &(_, last)) in slides.iter_mut().zip(pages) {
+        // Seções com o mesmo título continuam distintas: compara a posição no sumário
+        let section = outline
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.level == 0 && entry.page <= last)
+            .max_by_key(|(_, entry)| entry.page);
+        if let Some((position, entry)) = section {
+            if current_section != Some(position) {
+                slide.section = Some(entry.title.clone());
+                current_section = Some(position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, page: usize, level: usize) -> OutlineEntry {
+        OutlineEntry { title: title.to_string(), page, level }
+    }
+
+    fn slides(count: usize) -> Vec<Slide> {
+        vec![Slide::default(); count]
+    }
+
+    fn names(slides: &[Slide]) -> Vec<(&str, Option<&str>)> {
+        slides.iter().map(|s| (s.title.as_str(), s.section.as_deref())).collect()
+    }
+
+    #[test]
+    fn titles_and_sections_in_page_order() {
+        let outline = [
+            entry("Introdução", 0, 0),
+            entry("Motivação", 1, 1),
+            entry("Detalhe", 1, 2),
+            entry("Resultados", 3, 0),
+            entry("Resultados", 5, 0),
+        ];
+        // A página 3 foi omitida: a entrada dela vai para o slide seguinte
+        let pages = [(0, 0), (1, 1), (2, 2), (4, 4), (5, 6)];
+        let labels = [None, None, Some("iii".to_string()), Some("5".to_string()), None];
+        let mut deck = slides(5);
+        apply(&mut deck, &pages, &outline, &labels);
+        assert_eq!(
+            names(&deck),
+            [
+                ("Introdução", Some("Introdução")),
+                ("Detalhe", None),
+                ("iii", None),
+                ("Resultados", Some("Resultados")),
+                // Mesmo título, outra entrada: outra seção
+                ("Resultados", Some("Resultados")),
+            ]
+        );
+    }
+
+    #[test]
+    fn reordered_selection_keeps_titles() {
+        let outline = [entry("Abertura", 0, 0), entry("Meio", 2, 0), entry("Fim", 4, 0)];
+        // Seleção "5,1-3"
+        let pages = [(4, 4), (0, 0), (1, 1), (2, 2)];
+        let mut deck = slides(4);
+        apply(&mut deck, &pages, &outline, &[None, None, None, None]);
+        assert_eq!(
+            names(&deck),
+            [("Fim", Some("Fim")), ("Abertura", Some("Abertura")), ("", None), ("Meio", Some("Meio"))]
+        );
+    }
+}
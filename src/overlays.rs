@@ -0,0 +1,212 @@
+//! Overlays do Beamer (`\pause`, `\only`, `\uncover`)
+//! Agrupa páginas consecutivas que pertencem ao mesmo frame
+
+use image::{imageops, Rgba, RgbaImage};
+
+/// O que fazer com as páginas de overlay de um mesmo frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlayMode {
+    /// Uma página por slide, como no PDF
+    #[default]
+    Off,
+    /// Apenas a última página de cada frame
+    LastFrame,
+    /// Um slide por frame: a primeira página e, por cima, uma camada por passo do overlay
+    Builds,
+}
+
+/// Largura das miniaturas comparadas quando o PDF não tem rótulos de página
+const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Diferença por canal abaixo da qual dois pixels são considerados iguais
+const PIXEL_TOLERANCE: u8 = 24;
+
+/// Fração mínima dos pixels alterados que precisa ser fundo na página anterior
+const MIN_ADDED_ON_BACKGROUND: f32 = 0.9;
+
+/// Marca quais posições começam um frame a partir dos rótulos das páginas
+///
+/// O Beamer dá a todas as páginas de um frame o mesmo rótulo (o número do frame).
+/// `indices` são os índices no documento; só páginas vizinhas no PDF são agrupadas.
+/// Retorna `None` se o documento não tem rótulos.
+pub(crate) fn frame_starts_from_labels(indices: &[usize], labels: &[Option<String>]) -> Option<Vec<bool>> {
+    if labels.iter().all(|l| l.as_deref().is_none_or(str::is_empty)) {
+        return None;
+    }
+
+    let starts = (0..indices.len())
+        .map(|i| {
+            i == 0
+                || indices[i] != indices[i - 1] + 1
+                || labels[i].as_deref().is_none_or(str::is_empty)
+                || labels[i] != labels[i - 1]
+        })
+        .collect();
+    Some(starts)
+}
+
+/// Só a última página de cada frame, para `OverlayMode::LastFrame` com rótulos
+///
+/// Retorna as páginas mantidas e os inícios de frame delas: cada uma começa o seu, já que
+/// os rótulos separaram os frames. Comparar as miniaturas de novo juntaria frames vizinhos
+/// em que o seguinte só acrescenta conteúdo.
+pub(crate) fn last_pages(indices: &[usize], starts: &[bool]) -> (Vec<usize>, Vec<bool>) {
+    let kept: Vec<usize> = indices
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| starts.get(i + 1).copied().unwrap_or(true))
+        .map(|(_, &index)| index)
+        .collect();
+    let starts = vec![true; kept.len()];
+    (kept, starts)
+}
+
+/// Miniatura usada para comparar páginas consecutivas
+pub(crate) struct Thumbnail(RgbaImage);
+
+impl Thumbnail {
+    pub(crate) fn new(page: &RgbaImage) -> Self {
+        let height = (THUMBNAIL_WIDTH as f32 * page.height() as f32 / page.width().max(1) as f32).max(1.0) as u32;
+        Self(imageops::resize(page, THUMBNAIL_WIDTH, height, imageops::FilterType::Triangle))
+    }
+
+    /// `next` parece um passo de overlay desta página: só acrescenta conteúdo sobre o fundo
+    ///
+    /// Trocas de título ou de conteúdo alteram pixels que não eram fundo e iniciam um novo frame.
+    pub(crate) fn continues_into(&self, next: &Thumbnail) -> bool {
+        if self.0.dimensions() != next.0.dimensions() {
+            return false;
+        }
+
+        let background = self.background();
+        let mut changed = 0usize;
+        let mut on_background = 0usize;
+        for (before, after) in self.0.pixels().zip(next.0.pixels()) {
+            if !similar(before, after) {
+                changed += 1;
+                if similar(before, &background) {
+                    on_background += 1;
+                }
+            }
+        }
+
+        changed == 0 || on_background as f32 / changed as f32 >= MIN_ADDED_ON_BACKGROUND
+    }
+
+    /// Cor mais comum da miniatura (cores agrupadas em 4 bits por canal)
+    fn background(&self) -> Rgba<u8> {
+        let mut counts = std::collections::HashMap::new();
+        for pixel in self.0.pixels() {
+            let key = [pixel[0] >> 4, pixel[1] >> 4, pixel[2] >> 4];
+            counts.entry(key).or_insert((0usize, *pixel)).0 += 1;
+        }
+        counts.into_values().max_by_key(|(count, _)| *count).map(|(_, pixel)| pixel).unwrap_or(Rgba([255; 4]))
+    }
+}
+
+/// Camada com os pixels que mudam de `previous` para `next` (o resto transparente)
+///
+/// Empilhar as camadas sobre a primeira página reproduz cada passo do overlay.
+/// Retorna `None` se as páginas são iguais ou têm tamanhos diferentes.
+pub(crate) fn build_layer(previous: &RgbaImage, next: &RgbaImage) -> Option<RgbaImage> {
+    if previous.dimensions() != next.dimensions() {
+        return None;
+    }
+
+    let mut any = false;
+    let layer = RgbaImage::from_fn(next.width(), next.height(), |x, y| {
+        let after = next.get_pixel(x, y);
+        if previous.get_pixel(x, y) == after {
+            Rgba([0, 0, 0, 0])
+        } else {
+            any = true;
+            *after
+        }
+    });
+    any.then_some(layer)
+}
+
+fn similar(a: &Rgba<u8>, b: &Rgba<u8>) -> bool {
+    a.0.iter().zip(b.0.iter()).all(|(x, y)| x.abs_diff(*y) <= PIXEL_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Página branca com retângulos pretos `(x, y, largura, altura)`
+    fn page(boxes: &[(u32, u32, u32, u32)]) -> RgbaImage {
+        let mut page = RgbaImage::from_pixel(640, 360, Rgba([255, 255, 255, 255]));
+        for &(x, y, width, height) in boxes {
+            for (px, py) in (x..x + width).flat_map(|px| (y..y + height).map(move |py| (px, py))) {
+                page.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+            }
+        }
+        page
+    }
+
+    fn labels(labels: &[&str]) -> Vec<Option<String>> {
+        labels.iter().map(|label| Some(label.to_string())).collect()
+    }
+
+    #[test]
+    fn last_pages_of_labelled_frames_stay_separate() {
+        // Frame 1: título e um item; frame 2: o mesmo modelo, só com um item a mais
+        let title = (40, 20, 400, 30);
+        let pages = [
+            page(&[title]),
+            page(&[title, (40, 100, 300, 20)]),
+            page(&[title, (40, 100, 300, 20), (40, 140, 300, 20)]),
+            page(&[title, (40, 100, 300, 20), (40, 140, 300, 20), (40, 180, 300, 20)]),
+        ];
+        let starts = frame_starts_from_labels(&[0, 1, 2, 3], &labels(&["1", "1", "2", "2"])).unwrap();
+        assert_eq!(starts, vec![true, false, true, false]);
+
+        let (kept, starts) = last_pages(&[0, 1, 2, 3], &starts);
+        assert_eq!(kept, vec![1, 3]);
+        assert_eq!(starts, vec![true, true]);
+        // Pelas miniaturas as duas últimas páginas seriam um frame só
+        assert!(Thumbnail::new(&pages[1]).continues_into(&Thumbnail::new(&pages[3])));
+    }
+
+    #[test]
+    fn frames_from_labels() {
+        assert_eq!(frame_starts_from_labels(&[0, 1], &[None, None]), None);
+        assert_eq!(frame_starts_from_labels(&[0, 1], &labels(&["", ""])), None);
+        let starts = frame_starts_from_labels(&[0, 1, 2, 4, 5, 6], &labels(&["1", "1", "2", "2", "2", ""]));
+        // A página 4 segue a 2 na seleção, mas não no PDF; sem rótulo, a página começa um frame
+        assert_eq!(starts, Some(vec![true, false, true, true, false, true]));
+    }
+
+    #[test]
+    fn thumbnails_continue_only_when_content_is_added() {
+        let title = (40, 20, 400, 30);
+        let first = Thumbnail::new(&page(&[title, (40, 100, 300, 20)]));
+        // O slide seguinte só acrescenta um item: continua o frame
+        assert!(first.continues_into(&Thumbnail::new(&page(&[title, (40, 100, 300, 20), (40, 140, 300, 20)]))));
+        assert!(first.continues_into(&Thumbnail::new(&page(&[title, (40, 100, 300, 20)]))));
+        // Conteúdo trocado: o item some e outro aparece no lugar do título
+        assert!(!first.continues_into(&Thumbnail::new(&page(&[(40, 100, 400, 30)]))));
+        // Outra proporção
+        let tall = RgbaImage::from_pixel(360, 640, Rgba([255, 255, 255, 255]));
+        assert!(!first.continues_into(&Thumbnail::new(&tall)));
+    }
+
+    #[test]
+    fn layers_hold_only_the_added_pixels() {
+        let title = (40, 20, 400, 30);
+        let before = page(&[title]);
+        let after = page(&[title, (40, 100, 300, 20)]);
+        assert_eq!(build_layer(&before, &before), None);
+        assert_eq!(build_layer(&before, &RgbaImage::new(320, 180)), None);
+
+        let layer = build_layer(&before, &after).unwrap();
+        assert_eq!(layer.get_pixel(50, 110), &Rgba([0, 0, 0, 255]));
+        assert_eq!(layer.get_pixel(50, 30)[3], 0);
+        assert_eq!(layer.get_pixel(600, 300)[3], 0);
+        // A camada sobre a página anterior reproduz a seguinte
+        let mut stacked = before.clone();
+        imageops::overlay(&mut stacked, &layer, 0, 0);
+        assert_eq!(stacked, after);
+    }
+}
@@ -0,0 +1,263 @@
+//! Seleção de páginas: expressões como "1-5,8,12-", "odd", "last 3"
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Um termo da expressão de seleção
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// Intervalo fechado (1-based); `end = None` vai até a última página
+    Range { start: usize, end: Option<usize> },
+    /// Páginas ímpares (1, 3, 5, ...)
+    Odd,
+    /// Páginas pares (2, 4, 6, ...)
+    Even,
+    /// As últimas `n` páginas
+    Last(usize),
+}
+
+/// Seleção de páginas, interpretada a partir de uma expressão textual
+///
+/// Termos separados por vírgula, aplicados na ordem em que aparecem:
+/// - `7` — uma página
+/// - `1-5` — intervalo; `12-` vai até o fim e `-3` começa na primeira
+/// - `odd` / `even` — páginas ímpares / pares
+/// - `last` / `last 3` — a última página / as últimas 3
+/// - `all` ou expressão vazia — todas as páginas
+///
+/// Páginas repetidas são renderizadas apenas na primeira ocorrência.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSelection {
+    /// Vazio = todas as páginas
+    terms: Vec<Term>,
+}
+
+/// Expressão de seleção inválida ou incompatível com o documento
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSelectionError(String);
+
+impl fmt::Display for PageSelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PageSelectionError {}
+
+impl PageSelectionError {
+    /// A seleção não deixou nenhuma página de um documento (ou de vários juntados) com `page_count` páginas
+    pub(crate) fn nothing_selected(page_count: usize) -> Self {
+        Self(format!("nenhuma página selecionada (o documento tem {} página(s))", page_count))
+    }
+}
+
+impl PageSelection {
+    /// Todas as páginas do documento
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Interpreta uma expressão de seleção
+    pub fn parse(expression: &str) -> Result<Self, PageSelectionError> {
+        let mut terms = Vec::new();
+
+        for raw in expression.split(',') {
+            let term = raw.trim().to_ascii_lowercase();
+            if term.is_empty() {
+                if expression.trim().is_empty() {
+                    continue;
+                }
+                return Err(PageSelectionError(format!("termo vazio em '{}'", expression.trim())));
+            }
+
+            match term.as_str() {
+                "all" => return Ok(Self::all()),
+                "odd" => terms.push(Term::Odd),
+                "even" => terms.push(Term::Even),
+                "last" => terms.push(Term::Last(1)),
+                _ if term.starts_with("last") => {
+                    let count = parse_page_number(term["last".len()..].trim(), &term)?;
+                    terms.push(Term::Last(count));
+                }
+                _ => match term.split_once('-') {
+                    Some((start, end)) => {
+                        let start = match start.trim() {
+                            "" => 1,
+                            s => parse_page_number(s, &term)?,
+                        };
+                        let end = match end.trim() {
+                            "" => None,
+                            e => Some(parse_page_number(e, &term)?),
+                        };
+                        if let Some(end) = end {
+                            if end < start {
+                                return Err(PageSelectionError(format!(
+                                    "intervalo invertido '{}': {} é maior que {}",
+                                    term, start, end
+                                )));
+                            }
+                        }
+                        terms.push(Term::Range { start, end });
+                    }
+                    None => {
+                        let page = parse_page_number(&term, &term)?;
+                        terms.push(Term::Range { start: page, end: Some(page) });
+                    }
+                },
+            }
+        }
+
+        Ok(Self { terms })
+    }
+
+    /// Resolve a seleção para índices de página (0-based) de um documento com `page_count` páginas
+    pub fn resolve(&self, page_count: usize) -> Result<Vec<usize>, PageSelectionError> {
+        if self.terms.is_empty() {
+            return Ok((0..page_count).collect());
+        }
+
+        let mut selected = vec![false; page_count];
+        let mut pages = Vec::new();
+        let mut push = |index: usize| {
+            if !selected[index] {
+                selected[index] = true;
+                pages.push(index);
+            }
+        };
+
+        for term in &self.terms {
+            match *term {
+                Term::Range { start, end } => {
+                    let end_page = end.unwrap_or(page_count);
+                    if start > page_count || end_page > page_count {
+                        return Err(PageSelectionError(format!(
+                            "página {} fora do intervalo: o documento tem {} página(s)",
+                            if start > page_count { start } else { end_page },
+                            page_count
+                        )));
+                    }
+                    (start..=end_page).for_each(|page| push(page - 1));
+                }
+                Term::Odd => (0..page_count).step_by(2).for_each(&mut push),
+                Term::Even => (1..page_count).step_by(2).for_each(&mut push),
+                Term::Last(count) => {
+                    if count > page_count {
+                        return Err(PageSelectionError(format!(
+                            "'last {}' pede mais páginas do que o documento tem ({})",
+                            count, page_count
+                        )));
+                    }
+                    (page_count - count..page_count).for_each(&mut push);
+                }
+            }
+        }
+
+        if pages.is_empty() {
+            return Err(PageSelectionError::nothing_selected(page_count));
+        }
+
+        Ok(pages)
+    }
+}
+
+impl FromStr for PageSelection {
+    type Err = PageSelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn parse_page_number(text: &str, term: &str) -> Result<usize, PageSelectionError> {
+    match text.parse::<usize>() {
+        Ok(0) => Err(PageSelectionError(format!("'{}': as páginas começam em 1", term))),
+        Ok(n) => Ok(n),
+        Err(_) => Err(PageSelectionError(format!("termo inválido '{}'", term))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(expression: &str, page_count: usize) -> Result<Vec<usize>, PageSelectionError> {
+        PageSelection::parse(expression)?.resolve(page_count)
+    }
+
+    #[test]
+    fn ranges_and_single_pages() {
+        assert_eq!(pages("1-5,8,12-", 14).unwrap(), vec![0, 1, 2, 3, 4, 7, 11, 12, 13]);
+        assert_eq!(pages("-3", 10).unwrap(), vec![0, 1, 2]);
+        assert_eq!(pages("7", 10).unwrap(), vec![6]);
+    }
+
+    #[test]
+    fn odd_and_even() {
+        assert_eq!(pages("odd", 5).unwrap(), vec![0, 2, 4]);
+        assert_eq!(pages("even", 5).unwrap(), vec![1, 3]);
+        assert_eq!(pages("EVEN,odd", 4).unwrap(), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn last_pages() {
+        assert_eq!(pages("last", 10).unwrap(), vec![9]);
+        assert_eq!(pages("last 3", 10).unwrap(), vec![7, 8, 9]);
+        assert_eq!(pages("last3", 10).unwrap(), vec![7, 8, 9]);
+        assert!(pages("last 11", 10).is_err());
+        assert!(PageSelection::parse("last 0").is_err());
+    }
+
+    #[test]
+    fn repeated_pages_keep_the_first_occurrence() {
+        assert_eq!(pages("3,1-4,odd", 5).unwrap(), vec![2, 0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn inverted_range_is_rejected() {
+        let error = PageSelection::parse("5-2").unwrap_err();
+        assert!(error.to_string().contains("invertido"), "{}", error);
+    }
+
+    #[test]
+    fn page_zero_is_rejected() {
+        assert!(PageSelection::parse("0").is_err());
+        assert!(PageSelection::parse("0-3").is_err());
+        assert!(PageSelection::parse("2-0").is_err());
+    }
+
+    #[test]
+    fn pages_out_of_range() {
+        assert!(pages("11", 10).is_err());
+        assert!(pages("8-12", 10).is_err());
+        assert!(pages("12-", 10).is_err());
+        assert_eq!(pages("10-", 10).unwrap(), vec![9]);
+    }
+
+    #[test]
+    fn empty_results() {
+        assert!(pages("even", 1).is_err());
+        assert!(pages("odd", 0).is_err());
+        // Sem termos não há o que resolver: fica a cargo de quem chama
+        assert_eq!(pages("all", 0).unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn whitespace_and_empty_terms() {
+        assert_eq!(pages("  1 - 2 ,  4 ", 5).unwrap(), vec![0, 1, 3]);
+        assert!(PageSelection::parse("").unwrap().is_all());
+        assert!(PageSelection::parse("   ").unwrap().is_all());
+        assert!(PageSelection::parse("1,,3").is_err());
+        assert!(PageSelection::parse("1,").is_err());
+        assert!(PageSelection::parse("abc").is_err());
+    }
+
+    #[test]
+    fn all_overrides_other_terms() {
+        assert!(PageSelection::parse("1-3,all").unwrap().is_all());
+        assert_eq!("ALL".parse::<PageSelection>().unwrap(), PageSelection::all());
+    }
+}
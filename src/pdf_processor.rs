@@ -1,78 +1,1024 @@
 //! Módulo para processamento de PDFs
 //! Usa pdfium-render para renderizar páginas como imagens
 
-use anyhow::{Context, Result};
+use crate::editable::{self, EditablePage, PageObject, TextBox};
+use crate::error::Pdf2KeyError;
+use crate::handout::{self, Handout, PageView};
+use crate::images::{EmbeddedImage, ImageData};
+use crate::links::{LinkTarget, PageLink, PixelRect};
+use crate::outline::OutlineEntry;
+use crate::page_selection::{PageSelection, PageSelectionError};
+use crate::trim::{self, PageBox, PageRegion, TrimSource};
+use crate::vector::{self, Matrix, Paint, Segment, SvgBuilder, TextStyle, VectorPage};
 use image::{DynamicImage, RgbaImage};
 use pdfium_render::prelude::*;
-use std::path::Path;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-/// Carrega e renderiza todas as páginas de um PDF como imagens
+/// Carrega a biblioteca PDFium e abre documentos PDF
 pub struct PdfProcessor {
     pdfium: Pdfium,
 }
 
+/// Senha de um PDF protegido
+///
+/// Nunca aparece em `Debug` nem em mensagens de erro, para não ir parar em logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password(String);
+
+impl Password {
+    pub fn new(password: impl Into<String>) -> Self {
+        Self(password.into())
+    }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(***)")
+    }
+}
+
+/// Páginas do Beamer com `show notes on second screen`: slide e notas lado a lado
+///
+/// Quando a página é dividida, apenas a metade do slide é renderizada e o texto da
+/// outra metade vira as notas do apresentador.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BeamerNotes {
+    /// Páginas usadas inteiras
+    #[default]
+    Off,
+    /// Notas à direita (`second screen=right`, o padrão do Beamer)
+    Right,
+    /// Notas à esquerda (`second screen=left`)
+    Left,
+    /// Divide apenas páginas com largura dupla, assumindo as notas à direita
+    Auto,
+}
+
+/// Tamanho das imagens renderizadas
+///
+/// Com `Fit`, cada página é escalada pelo seu próprio tamanho: um pôster e uma página
+/// pequena resultam em imagens do mesmo porte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Pontos por polegada, qualquer que seja o tamanho da página
+    Dpi(u16),
+    /// A página (ou a metade do slide, nas páginas divididas) cabe em `width`×`height`
+    /// pixels, mantendo a proporção
+    Fit { width: u32, height: u32 },
+}
+
+impl Resolution {
+    pub const FULL_HD: Resolution = Resolution::Fit { width: 1920, height: 1080 };
+    pub const UHD: Resolution = Resolution::Fit { width: 3840, height: 2160 };
+
+    /// Tamanho do slide do Keynote (1920×1080) multiplicado por `factor`
+    pub fn keynote_slide(factor: u32) -> Self {
+        let (width, height) = crate::keynote_native::DEFAULT_SLIDE_SIZE;
+        Resolution::Fit { width: width * factor.max(1), height: height * factor.max(1) }
+    }
+
+    /// Interpreta `1920x1080`, `1080p`, `4k`, `keynote` ou `keynote2x`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace('×', "x");
+        match name.as_str() {
+            "1080p" | "fullhd" => Some(Resolution::FULL_HD),
+            "4k" | "2160p" | "uhd" => Some(Resolution::UHD),
+            "keynote" => Some(Resolution::keynote_slide(1)),
+            _ => {
+                if let Some(factor) = name.strip_prefix("keynote") {
+                    let factor = factor.trim_start_matches('x').trim_end_matches('x');
+                    return factor.parse().ok().filter(|f| *f > 0).map(Resolution::keynote_slide);
+                }
+                let (width, height) = name.split_once('x')?;
+                let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+                (width > 0 && height > 0).then_some(Resolution::Fit { width, height })
+            }
+        }
+    }
+
+    /// Pixels por ponto para uma área visível de `width`×`height` pontos
+    pub fn scale(self, width: f32, height: f32) -> f32 {
+        match self {
+            Resolution::Dpi(dpi) => dpi as f32 / 72.0,
+            Resolution::Fit { width: max_width, height: max_height } => {
+                (max_width as f32 / width.max(1.0)).min(max_height as f32 / height.max(1.0))
+            }
+        }
+    }
+}
+
+/// Padrão: o dobro do slide do Keynote, nítido em telas Retina e projetores 4K
+impl Default for Resolution {
+    fn default() -> Self {
+        Resolution::keynote_slide(2)
+    }
+}
+
+/// Proporção (largura / altura) a partir da qual `BeamerNotes::Auto` considera a página dupla.
+/// Slides comuns vão até 16:9 (1,78); um slide 4:3 com notas ao lado tem 8:3 (2,67).
+const DOUBLE_WIDTH_ASPECT: f32 = 2.2;
+
+/// Limite de entradas lidas do sumário; protege contra sumários malformados com ciclos
+const MAX_OUTLINE_ENTRIES: usize = 10_000;
+
+/// Tamanho da renderização reduzida usada para achar o conteúdo pelos pixels
+const TRIM_PREVIEW: Resolution = Resolution::Fit { width: 1000, height: 1000 };
+
+/// Documento PDF aberto, renderizado sob demanda (uma página por vez)
+///
+/// Pode juntar vários PDFs (ver [`PdfDocumentHandle::append`]): as páginas ficam numeradas
+/// em sequência, como num documento só.
+pub struct PdfDocumentHandle<'a> {
+    documents: Vec<PdfDocument<'a>>,
+    beamer_notes: BeamerNotes,
+    /// Regiões das páginas com as margens cortadas (ver [`crate::trim`])
+    trim_regions: HashMap<usize, PageRegion>,
+    /// Slides de cada página com as apostilas divididas (ver [`crate::handout`]); vazio
+    /// quando cada índice é a própria página
+    views: Vec<PageView>,
+}
+
 impl PdfProcessor {
     /// Cria uma nova instância do processador de PDF
-    pub fn new() -> Result<Self> {
+    pub fn new() -> Result<Self, Pdf2KeyError> {
         // Tenta carregar a biblioteca pdfium de vários locais
-        let pdfium = Pdfium::new(
-            Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./lib/"))
-                .or_else(|_| Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./")))
-                .or_else(|_| Pdfium::bind_to_system_library())
-                .context("Não foi possível encontrar a biblioteca PDFium. Verifique se lib/libpdfium.dylib existe.")?,
-        );
-        
-        Ok(Self { pdfium })
-    }
-
-    /// Renderiza todas as páginas do PDF como imagens
-    /// 
+        let searched = vec![
+            Pdfium::pdfium_platform_library_name_at_path("./lib/"),
+            Pdfium::pdfium_platform_library_name_at_path("./"),
+            // Caminhos padrão do sistema
+            PathBuf::from(Pdfium::pdfium_platform_library_name()),
+        ];
+        let bindings = Pdfium::bind_to_library(&searched[0])
+            .or_else(|_| Pdfium::bind_to_library(&searched[1]))
+            .or_else(|_| Pdfium::bind_to_system_library())
+            .map_err(|_| Pdf2KeyError::PdfiumNotFound { searched })?;
+
+        Ok(Self { pdfium: Pdfium::new(bindings) })
+    }
+
+    /// Abre um PDF sem renderizar nenhuma página
+    pub fn open(&self, pdf_path: &Path) -> Result<PdfDocumentHandle<'_>, Pdf2KeyError> {
+        self.open_with_password(pdf_path, None)
+    }
+
+    /// Abre um PDF, protegido ou não, sem renderizar nenhuma página
+    ///
+    /// Sem senha, um PDF protegido falha com `PasswordRequired`; com a senha errada, `IncorrectPassword`.
+    pub fn open_with_password<'a>(
+        &'a self,
+        pdf_path: &Path,
+        password: Option<&'a Password>,
+    ) -> Result<PdfDocumentHandle<'a>, Pdf2KeyError> {
+        let document = self.pdfium
+            .load_pdf_from_file(pdf_path, password.map(|p| p.0.as_str()))
+            .map_err(|e| match e {
+                PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError) => match password {
+                    None => Pdf2KeyError::PasswordRequired { path: pdf_path.to_path_buf() },
+                    Some(_) => Pdf2KeyError::IncorrectPassword { path: pdf_path.to_path_buf() },
+                },
+                e => Pdf2KeyError::PdfOpen { path: pdf_path.to_path_buf(), reason: e.to_string() },
+            })?;
+
+        Ok(PdfDocumentHandle { documents: vec![document], beamer_notes: BeamerNotes::Off, trim_regions: HashMap::new(), views: Vec::new() })
+    }
+
+    /// Renderiza as páginas do PDF uma a uma, entregando cada imagem a `on_page`
+    ///
+    /// Apenas uma página fica em memória por vez, independente do tamanho do documento.
+    ///
     /// # Arguments
     /// * `pdf_path` - Caminho para o arquivo PDF
-    /// * `dpi` - Resolução de renderização (recomendado: 150-300)
-    /// 
+    /// * `resolution` - Tamanho das imagens (DPI fixo ou tamanho em pixels)
+    /// * `password` - Senha, se o PDF for protegido
+    /// * `selection` - Páginas a renderizar; as demais nunca são renderizadas
+    /// * `on_page` - Recebe o índice (no documento) e a imagem de cada página
+    ///
     /// # Returns
-    /// Vetor de imagens, uma para cada página
-    pub fn render_pages(&self, pdf_path: &Path, dpi: u16) -> Result<Vec<DynamicImage>> {
-        let document = self.pdfium
-            .load_pdf_from_file(pdf_path, None)
-            .context("Falha ao abrir o arquivo PDF")?;
+    /// Número de páginas renderizadas
+    pub fn render_pages<F, E>(
+        &self,
+        pdf_path: &Path,
+        password: Option<&Password>,
+        resolution: Resolution,
+        selection: &PageSelection,
+        mut on_page: F,
+    ) -> Result<usize, E>
+    where
+        F: FnMut(usize, DynamicImage) -> Result<(), E>,
+        E: From<Pdf2KeyError>,
+    {
+        let document = self.open_with_password(pdf_path, password)?;
+        let indices = document.select_pages(selection).map_err(Pdf2KeyError::from)?;
+        let count = indices.len();
 
-        let pages = document.pages();
-        let page_count = pages.len();
-        let mut images = Vec::with_capacity(page_count as usize);
+        for (index, image) in indices.iter().copied().zip(document.pages(indices.clone(), resolution)) {
+            on_page(index, image?)?;
+        }
 
-        for (index, page) in pages.iter().enumerate() {
-            let render_config = PdfRenderConfig::new()
-                .set_target_width(
-                    (page.width().value * dpi as f32 / 72.0) as i32
-                )
-                .set_maximum_height(
-                    (page.height().value * dpi as f32 / 72.0) as i32
-                );
+        Ok(count)
+    }
+
+    /// Retorna o tamanho de uma página em pontos (1/72")
+    pub fn page_size(&self, pdf_path: &Path, index: usize) -> Result<(f32, f32), Pdf2KeyError> {
+        self.open(pdf_path)?.page_size(index)
+    }
+
+    /// Retorna o número de páginas no PDF
+    pub fn page_count(&self, pdf_path: &Path) -> Result<usize, Pdf2KeyError> {
+        Ok(self.open(pdf_path)?.page_count())
+    }
+}
+
+impl<'a> PdfDocumentHandle<'a> {
+    /// Define como tratar páginas do Beamer com as notas ao lado do slide
+    pub fn with_beamer_notes(mut self, beamer_notes: BeamerNotes) -> Self {
+        self.beamer_notes = beamer_notes;
+        self
+    }
+
+    /// Passa a mostrar só estas regiões das páginas (índices 0-based); as demais ficam inteiras
+    ///
+    /// Vale para imagens, links, objetos e SVGs. As regiões vêm de [`trim::page_regions`].
+    pub fn with_trim_regions(mut self, regions: HashMap<usize, PageRegion>) -> Self {
+        self.trim_regions = regions;
+        self
+    }
+
+    /// Acrescenta as páginas de `other` depois das deste documento
+    ///
+    /// Os índices de `other` passam a começar em `self.page_count()`. Vale a configuração
+    /// deste documento; junte os PDFs antes de dividir apostilas e cortar margens.
+    pub fn append(mut self, other: PdfDocumentHandle<'a>) -> Self {
+        self.documents.extend(other.documents);
+        self
+    }
+
+    /// Passa a usar os índices de `views` (um por slide) no lugar das páginas do PDF
+    ///
+    /// As views vêm de [`handout::page_views`]; seleção de páginas e margens devem ser
+    /// resolvidas depois, já com os novos índices.
+    pub fn with_page_views(mut self, views: Vec<PageView>) -> Self {
+        self.views = views;
+        self
+    }
+
+    /// Número de páginas do documento (ou de slides, com as apostilas divididas)
+    pub fn page_count(&self) -> usize {
+        match self.views.is_empty() {
+            true => self.documents.iter().map(|document| document.pages().len() as usize).sum(),
+            false => self.views.len(),
+        }
+    }
 
-            let bitmap = page
-                .render_with_config(&render_config)
-                .context(format!("Falha ao renderizar página {}", index + 1))?;
+    /// Resolve uma seleção para os índices (0-based) das páginas deste documento
+    pub fn select_pages(&self, selection: &PageSelection) -> Result<Vec<usize>, PageSelectionError> {
+        selection.resolve(self.page_count())
+    }
+
+    /// Tamanho de uma página em pontos (1/72"); em páginas divididas, só o do slide, e
+    /// com as margens cortadas, só o da região
+    pub fn page_size(&self, index: usize) -> Result<(f32, f32), Pdf2KeyError> {
+        let page = self.page(index)?;
+        let region = self.visible_region(&page, index);
+        Ok((region.width, region.height))
+    }
+
+    /// Área do slide na página, sem o corte das margens: a página inteira ou, em páginas
+    /// divididas, a metade do slide
+    pub fn slide_region(&self, index: usize) -> Result<PageRegion, Pdf2KeyError> {
+        let page = self.page(index)?;
+        Ok(self.slide_area(&page, index))
+    }
+
+    /// Área com conteúdo dentro do slide, sem folga; `None` se a página estiver em branco
+    /// ou não tiver a caixa pedida
+    pub fn content_bounds(&self, index: usize, source: TrimSource) -> Result<Option<PageRegion>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let slide = self.slide_area(&page, index);
+        let region = match source {
+            TrimSource::Pixels => {
+                let area = self.visible_area(&page, index, TRIM_PREVIEW);
+                let image = self.render(&page, index, TRIM_PREVIEW)?.into_rgba8();
+                trim::pixel_bounds(&image).map(|rect| area.to_region(&rect))
+            }
+            // Só os objetos que aparecem no slide (numa apostila, os da célula)
+            TrimSource::Objects => page
+                .objects()
+                .iter()
+                .filter_map(|object| object.bounds().ok())
+                .filter_map(|bounds| page_region(&page, &bounds.to_rect()).intersect(&slide))
+                .reduce(|a, b| a.union(&b)),
+            TrimSource::Box(kind) => {
+                let boundaries = page.boundaries();
+                let boundary = match kind {
+                    PageBox::Crop => boundaries.crop(),
+                    PageBox::Trim => boundaries.trim(),
+                    PageBox::Art => boundaries.art(),
+                };
+                boundary.ok().map(|boundary| page_region(&page, &boundary.bounds))
+            }
+        };
+        Ok(region.and_then(|region| region.intersect(&slide)))
+    }
+
+    /// Células dos slides numa página de apostila, na ordem dos slides; vazio se a página
+    /// não for dividida
+    pub fn handout_cells(&self, index: usize, handout: &Handout) -> Result<Vec<PageRegion>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let area = self.visible_area(&page, index, TRIM_PREVIEW);
+        let image = self.render(&page, index, TRIM_PREVIEW)?.into_rgba8();
+        Ok(handout::cells(&image, handout).iter().map(|rect| area.to_region(rect)).collect())
+    }
 
-            let image = bitmap
-                .as_image();
+    /// Todo o texto de uma página, na ordem de leitura do PDFium; numa célula de apostila,
+    /// só o texto dela
+    pub fn page_text(&self, index: usize) -> Result<String, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let text = page
+            .text()
+            .map_err(|e| Pdf2KeyError::PageText { page: index + 1, reason: e.to_string() })?;
+        Ok(match self.cell(index) {
+            Some(cell) => text.inside_rect(pdf_rect(&page, &cell)),
+            None => text.all(),
+        })
+    }
 
-            // Converte para DynamicImage
-            let rgba_image: RgbaImage = image.into_rgba8();
-            images.push(DynamicImage::ImageRgba8(rgba_image));
+    /// Rótulo da página (`/PageLabels`), se o PDF definir um
+    ///
+    /// As células de uma apostila não têm rótulo: o da página valeria para todos os slides dela.
+    pub fn page_label(&self, index: usize) -> Result<Option<String>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        if self.cell(index).is_some() {
+            return Ok(None);
         }
+        Ok(page.label().map(str::to_string))
+    }
 
+    /// Sumário (bookmarks) em ordem de leitura; entradas sem página de destino são ignoradas
+    ///
+    /// Com as apostilas divididas, cada entrada aponta para o primeiro slide da página.
+    pub fn outline(&self) -> Vec<OutlineEntry> {
+        let mut entries = Vec::new();
+        for (source, document) in self.documents.iter().enumerate() {
+            let (first, mut visited) = (entries.len(), 0);
+            collect_bookmarks(document.bookmarks().root(), 0, &mut entries, &mut visited);
+            let offset = self.first_page(source);
+            entries[first..].iter_mut().for_each(|entry| entry.page += offset);
+        }
+        entries
+            .into_iter()
+            .filter_map(|entry| Some(OutlineEntry { page: self.view_of(entry.page)?, ..entry }))
+            .collect()
+    }
+
+    /// Links da página, com os retângulos na escala de `render_page` com a mesma `resolution`
+    ///
+    /// Em páginas divididas, só os links da metade do slide. Links sem destino
+    /// suportado (ex: abrir outro arquivo) são ignorados.
+    pub fn page_links(&self, index: usize, resolution: Resolution) -> Result<Vec<PageLink>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let area = self.visible_area(&page, index, resolution);
+        // Destinos são páginas do PDF desta página
+        let offset = self.locate(index).map_or(0, |(source, _)| self.first_page(source));
+
+        let mut links = Vec::new();
+        for link in page.links().iter() {
+            let target = link
+                .destination()
+                .and_then(|d| d.page_index().ok())
+                .and_then(|page| self.view_of(offset + page as usize))
+                .map(LinkTarget::Page)
+                .or_else(|| {
+                    let action = link.action()?;
+                    if let Some(uri) = action.as_uri_action() {
+                        return uri.uri().ok().filter(|u| !u.is_empty()).map(LinkTarget::Uri);
+                    }
+                    let destination = action.as_local_destination_action()?.destination().ok()?;
+                    destination.page_index().ok().and_then(|page| self.view_of(offset + page as usize)).map(LinkTarget::Page)
+                });
+            let (Some(target), Ok(rect)) = (target, link.rect()) else {
+                continue;
+            };
+
+            if let Some(rect) = area.to_pixels(&rect) {
+                links.push(PageLink { rect, target });
+            }
+        }
+        Ok(links)
+    }
+
+    /// Decompõe a página para o modo editável (ver [`crate::editable`])
+    ///
+    /// Textos visíveis e imagens (do nível superior da página) viram objetos com retângulos
+    /// na escala de `render_page`; o fundo é renderizado sem eles.
+    pub fn render_editable(&self, index: usize, resolution: Resolution) -> Result<EditablePage, Pdf2KeyError> {
+        self.decompose(index, resolution, true)
+    }
+
+    /// Renderiza o fundo sem as imagens, que voltam como objetos na resolução original
+    /// (ver [`crate::images`]); os textos continuam no fundo
+    pub fn render_without_images(&self, index: usize, resolution: Resolution) -> Result<EditablePage, Pdf2KeyError> {
+        self.decompose(index, resolution, false)
+    }
+
+    /// Imagens do nível superior da página, na resolução original e na ordem de pintura
+    ///
+    /// Os retângulos estão na escala de `render_page` com `resolution`.
+    pub fn page_images(&self, index: usize, resolution: Resolution) -> Result<Vec<EmbeddedImage>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let area = self.visible_area(&page, index, resolution);
+        let mut images = Vec::new();
+        for object in page.objects().iter() {
+            let (Some(image), Some(rect)) = (object.as_image_object(), object_rect(&object, &area)) else {
+                continue;
+            };
+            images.push(self.embedded_image(image, rect, index)?);
+        }
         Ok(images)
     }
 
-    /// Retorna o número de páginas no PDF
-    #[allow(dead_code)]
-    pub fn page_count(&self, pdf_path: &Path) -> Result<usize> {
-        let document = self.pdfium
-            .load_pdf_from_file(pdf_path, None)
-            .context("Falha ao abrir o arquivo PDF")?;
-        
-        Ok(document.pages().len() as usize)
+    /// Remove da página os textos (se `texts`) e as imagens e renderiza o que sobra
+    ///
+    /// A remoção acontece só na cópia da página em memória e é descartada ao final, sem
+    /// alterar o documento.
+    fn decompose(&self, index: usize, resolution: Resolution, texts: bool) -> Result<EditablePage, Pdf2KeyError> {
+        let mut page = self.page(index)?;
+        let area = self.visible_area(&page, index, resolution);
+
+        let mut objects = Vec::new();
+        let mut removed = Vec::new();
+        for (position, object) in page.objects().iter().enumerate() {
+            let Some(rect) = object_rect(&object, &area) else {
+                continue;
+            };
+            if let Some(text) = object.as_text_object().filter(|_| texts) {
+                // Texto invisível (ex: OCR sobre uma digitalização) fica onde está
+                if text.render_mode() == PdfPageTextRenderMode::Invisible || text.text().trim().is_empty() {
+                    continue;
+                }
+                let font = text.font();
+                let name = font.name();
+                let (bold, italic) = editable::font_style(&name);
+                let color = object.fill_color().map(|c| [c.red(), c.green(), c.blue()]).unwrap_or([0, 0, 0]);
+                objects.push(PageObject::Text(TextBox {
+                    rect,
+                    text: text.text(),
+                    font: editable::font_family(&name),
+                    size: text.scaled_font_size().value * area.scale,
+                    color,
+                    bold,
+                    italic: italic || font.is_italic(),
+                }));
+                removed.push(position);
+            } else if let Some(image) = object.as_image_object() {
+                objects.push(PageObject::Image(self.embedded_image(image, rect, index)?));
+                removed.push(position);
+            }
+        }
+
+        // Sem regenerar o conteúdo, as remoções se perdem quando a página é fechada
+        page.set_content_regeneration_strategy(PdfPageContentRegenerationStrategy::Manual);
+        for &position in removed.iter().rev() {
+            page.objects_mut()
+                .remove_object_at_index(position as PdfPageObjectIndex)
+                .map_err(|e| Pdf2KeyError::PageRender { page: index + 1, reason: e.to_string() })?;
+        }
+        let background = self.render(&page, index, resolution)?;
+
+        Ok(EditablePage { background, objects: editable::merge_runs(objects) })
+    }
+
+    /// Pixels originais de uma imagem da página
+    ///
+    /// JPEGs desenhados sem rotação nem transparência mantêm os bytes do PDF; as demais
+    /// imagens são decodificadas com máscara e transformações aplicadas.
+    fn embedded_image(&self, image: &PdfPageImageObject, rect: PixelRect, index: usize) -> Result<EmbeddedImage, Pdf2KeyError> {
+        let source = self.locate(index).map_or(0, |(source, _)| source);
+        let data = self
+            .image_data(image, source)
+            .map_err(|e| Pdf2KeyError::PageRender { page: index + 1, reason: e.to_string() })?;
+        Ok(EmbeddedImage { rect, data })
+    }
+
+    /// `source`: o PDF da imagem, entre os juntados
+    fn image_data(&self, image: &PdfPageImageObject, source: usize) -> Result<ImageData, PdfiumError> {
+        let decoded = image.get_processed_image(&self.documents[source])?;
+
+        let filters = image.filters();
+        let is_jpeg = filters.len() == 1 && filters.iter().all(|f| f.name() == "DCTDecode");
+        let plain_color = matches!(image.color_space(), Ok(PdfColorSpace::DeviceRGB | PdfColorSpace::DeviceGray));
+        let upright = image.matrix().is_ok_and(|m| m.a() > 0.0 && m.d() > 0.0 && m.b() == 0.0 && m.c() == 0.0);
+        let opaque = decoded.as_rgba8().is_none_or(|rgba| rgba.pixels().all(|p| p[3] == u8::MAX));
+
+        let data = match image.get_raw_image_data() {
+            Ok(bytes) if is_jpeg && plain_color && upright && opaque && !bytes.is_empty() => {
+                ImageData::Jpeg { bytes, width: decoded.width(), height: decoded.height() }
+            }
+            _ => ImageData::Decoded(decoded),
+        };
+        Ok(data)
+    }
+
+    /// Converte a página em SVG, na escala de `render_page` (ver [`crate::vector`])
+    ///
+    /// Sem `texts` ou sem `images`, os textos ou as imagens ficam de fora (eles seguem como
+    /// objetos nos modos editável e de imagens na resolução original).
+    pub fn render_svg(&self, index: usize, resolution: Resolution, texts: bool, images: bool) -> Result<VectorPage, Pdf2KeyError> {
+        let page = self.page(index)?;
+        let area = self.visible_area(&page, index, resolution);
+        // PDF: pontos com origem embaixo; SVG: pixels da imagem com origem em cima
+        let (x, y) = (area.offset.0 as f32, area.offset.1 as f32);
+        let (left, top) = area.origin;
+        let page_transform = [area.scale, 0.0, 0.0, -area.scale, -left * area.scale - x, top * area.scale - y];
+        let mut svg = SvgBuilder::new(area.width.max(1) as u32, area.height.max(1) as u32, page_transform);
+        let source = self.locate(index).map_or(0, |(source, _)| source);
+        let content = SvgContent { texts, images, source, nested: false, outer: IDENTITY };
+        for object in page.objects().iter() {
+            if let Err(reason) = self.svg_object(&object, &mut svg, &content) {
+                return Ok(VectorPage::Unsupported(reason));
+            }
+        }
+        Ok(VectorPage::Svg(svg.finish()))
+    }
+
+    /// Acrescenta um objeto da página ao SVG; `Err` traz o motivo para rasterizar a página
+    fn svg_object(&self, object: &PdfPageObject, svg: &mut SvgBuilder, content: &SvgContent) -> Result<(), &'static str> {
+        const UNREADABLE: &str = "objeto ilegível";
+        let clips = match object.get_clip_path() {
+            Some(clip) if !clip.is_empty() => {
+                // Recortes de objetos dentro de formulários não estão no espaço da página
+                if content.nested {
+                    return Err("recorte dentro de formulário");
+                }
+                (0..clip.len())
+                    .map(|i| clip.get(i).ok().and_then(|path| path_segments(&path)).and_then(vector::path_data))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(UNREADABLE)?
+            }
+            _ => Vec::new(),
+        };
+        let transform = object.matrix().map(|m| to_matrix(&m)).map_err(|_| UNREADABLE)?;
+        let color = |c: Result<PdfColor, PdfiumError>| c.map(|c| [c.red(), c.green(), c.blue(), c.alpha()]).unwrap_or([0, 0, 0, 255]);
+
+        match object {
+            PdfPageObject::Path(path) => {
+                let fill_mode = path.fill_mode().map_err(|_| UNREADABLE)?;
+                let stroked = path.is_stroked().unwrap_or(false);
+                if fill_mode == PdfPathFillMode::None && !stroked {
+                    return Ok(());
+                }
+                let (fill, stroke) = (color(object.fill_color()), color(object.stroke_color()));
+                if object.has_transparency() && fill[3] == u8::MAX && stroke[3] == u8::MAX {
+                    return Err("transparência ou mesclagem");
+                }
+                let d = path_segments(&path.segments()).and_then(vector::path_data).ok_or(UNREADABLE)?;
+                let paint = Paint {
+                    fill: (fill_mode != PdfPathFillMode::None).then_some(fill),
+                    even_odd: fill_mode == PdfPathFillMode::EvenOdd,
+                    stroke: stroked.then(|| (stroke, object.stroke_width().map(|w| w.value).unwrap_or(1.0))),
+                    join: match object.line_join() {
+                        Ok(PdfPageObjectLineJoin::Round) => "round",
+                        Ok(PdfPageObjectLineJoin::Bevel) => "bevel",
+                        _ => "miter",
+                    },
+                    cap: match object.line_cap() {
+                        Ok(PdfPageObjectLineCap::Round) => "round",
+                        Ok(PdfPageObjectLineCap::Square) => "square",
+                        _ => "butt",
+                    },
+                    dash: object.dash_array().map(|dash| dash.iter().map(|v| v.value).collect()).unwrap_or_default(),
+                    dash_phase: object.dash_phase().map(|p| p.value).unwrap_or(0.0),
+                };
+                let depth = svg.begin_group(None, &clips);
+                svg.path(&d, &transform, &paint);
+                svg.end_group(depth);
+            }
+            PdfPageObject::Text(text) if content.texts => {
+                let mode = text.render_mode();
+                let (filled, stroked) = match mode {
+                    PdfPageTextRenderMode::Invisible => return Ok(()),
+                    PdfPageTextRenderMode::FilledUnstroked => (true, false),
+                    PdfPageTextRenderMode::StrokedUnfilled => (false, true),
+                    PdfPageTextRenderMode::FilledThenStroked => (true, true),
+                    PdfPageTextRenderMode::Unknown => return Err(UNREADABLE),
+                    _ => return Err("texto usado como recorte"),
+                };
+                let string = text.text();
+                // Glifos sem Unicode (fontes com codificação própria) não têm como virar texto
+                if string.is_empty() || string.contains('\u{FFFD}') {
+                    return Err("texto sem Unicode");
+                }
+                if string.trim().is_empty() {
+                    return Ok(());
+                }
+                let (fill, stroke) = (color(object.fill_color()), color(object.stroke_color()));
+                if object.has_transparency() && fill[3] == u8::MAX && stroke[3] == u8::MAX {
+                    return Err("transparência ou mesclagem");
+                }
+                let font = text.font();
+                let name = font.name();
+                let (bold, italic) = editable::font_style(&name);
+                // Largura no espaço do texto: a fonte substituta é esticada até ela
+                let combined = vector::multiply(&transform, &content.outer);
+                let horizontal_scale = combined[0].hypot(combined[1]);
+                let length = object
+                    .bounds()
+                    .ok()
+                    .map(|b| b.to_rect().width().value / horizontal_scale)
+                    .filter(|l| l.is_finite());
+                let style = TextStyle {
+                    font: editable::font_family(&name),
+                    size: text.unscaled_font_size().value,
+                    bold: bold || font.is_bold_reenforced(),
+                    italic: italic || font.is_italic(),
+                    length,
+                };
+                let paint = Paint {
+                    fill: filled.then_some(fill),
+                    stroke: stroked.then(|| (stroke, object.stroke_width().map(|w| w.value).unwrap_or(1.0))),
+                    join: "miter",
+                    cap: "butt",
+                    ..Paint::default()
+                };
+                let depth = svg.begin_group(None, &clips);
+                svg.text(&string, &transform, &style, &paint);
+                svg.end_group(depth);
+            }
+            PdfPageObject::Image(image) if content.images => {
+                // Imagens giradas ou espelhadas chegam já transformadas pelo PDFium
+                let [a, b, c, d, ..] = transform;
+                if !(a > 0.0 && d > 0.0 && b == 0.0 && c == 0.0) {
+                    return Err("imagem girada ou espelhada");
+                }
+                let (mime, bytes) = match self.image_data(image, content.source).map_err(|_| UNREADABLE)? {
+                    ImageData::Jpeg { bytes, .. } => ("image/jpeg", bytes),
+                    ImageData::Decoded(decoded) => {
+                        let mut bytes = Vec::new();
+                        decoded
+                            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+                            .map_err(|_| UNREADABLE)?;
+                        ("image/png", bytes)
+                    }
+                };
+                let depth = svg.begin_group(None, &clips);
+                svg.image(&transform, mime, &bytes);
+                svg.end_group(depth);
+            }
+            PdfPageObject::XObjectForm(form) => {
+                let depth = svg.begin_group(Some(transform), &clips);
+                let inner = SvgContent { nested: true, outer: vector::multiply(&transform, &content.outer), ..*content };
+                for i in 0..form.len() {
+                    let child = form.get(i).map_err(|_| UNREADABLE)?;
+                    self.svg_object(&child, svg, &inner)?;
+                }
+                svg.end_group(depth);
+            }
+            // Textos e imagens extraídos como objetos
+            PdfPageObject::Text(_) | PdfPageObject::Image(_) => {}
+            PdfPageObject::Shading(_) => return Err("sombreamento"),
+            PdfPageObject::Unsupported(_) => return Err("objeto não suportado"),
+        }
+        Ok(())
+    }
+
+    /// Texto da metade de notas, se a página for dividida (ver [`BeamerNotes`])
+    pub fn notes_text(&self, index: usize) -> Result<Option<String>, Pdf2KeyError> {
+        let page = self.page(index)?;
+        // As notas são da página inteira, não de uma célula de apostila
+        let Some(side) = self.notes_side(&page).filter(|_| self.cell(index).is_none()) else {
+            return Ok(None);
+        };
+        let rect = notes_rect(side, crop_origin(&page), page.width().value, page.height().value);
+        let text = page
+            .text()
+            .map_err(|e| Pdf2KeyError::PageText { page: index + 1, reason: e.to_string() })?;
+        Ok(Some(text.inside_rect(rect)))
+    }
+
+    /// Renderiza uma única página como imagem RGBA
+    pub fn render_page(&self, index: usize, resolution: Resolution) -> Result<DynamicImage, Pdf2KeyError> {
+        let page = self.page(index)?;
+        self.render(&page, index, resolution)
+    }
+
+    fn render(&self, page: &PdfPage, index: usize, resolution: Resolution) -> Result<DynamicImage, Pdf2KeyError> {
+        // Mesma escala de `visible_area`, para que links e objetos caiam nos mesmos pixels
+        let area = self.visible_area(page, index, resolution);
+        let render_error = |e: PdfiumError| Pdf2KeyError::PageRender { page: index + 1, reason: e.to_string() };
+        // Célula de apostila: um bitmap do tamanho da célula, com a página deslocada por
+        // uma matriz para que o PDFium só rasterize a célula (a matriz desativa os campos de
+        // formulário). As demais áreas são recortadas da página inteira, com os formulários.
+        let (render_config, offset) = if self.cell(index).is_some() {
+            let (x, y) = (area.offset.0 as f32, area.offset.1 as f32);
+            let render_config = PdfRenderConfig::new()
+                .set_fixed_size(area.width.max(1), area.height.max(1))
+                .transform(area.scale, 0.0, 0.0, area.scale, -x, -y)
+                .map_err(render_error)?;
+            (render_config, (0, 0))
+        } else {
+            let render_config = PdfRenderConfig::new()
+                .set_target_width((page.width().value * area.scale).round() as i32)
+                .set_maximum_height((page.height().value * area.scale).round() as i32);
+            (render_config, area.offset)
+        };
+
+        let bitmap = page.render_with_config(&render_config).map_err(render_error)?;
+
+        // Converte para DynamicImage
+        let rgba_image: RgbaImage = bitmap.as_image().into_rgba8();
+
+        // Página dividida ou com as margens cortadas: fica só a área visível
+        let (x, y) = (offset.0.max(0) as u32, offset.1.max(0) as u32);
+        let (width, height) = (area.width.max(1) as u32, area.height.max(1) as u32);
+        let rgba_image = if (x, y) == (0, 0) && (width, height) == rgba_image.dimensions() {
+            rgba_image
+        } else {
+            let width = width.min(rgba_image.width().saturating_sub(x)).max(1);
+            let height = height.min(rgba_image.height().saturating_sub(y)).max(1);
+            image::imageops::crop_imm(&rgba_image, x, y, width, height).to_image()
+        };
+        Ok(DynamicImage::ImageRgba8(rgba_image))
+    }
+
+    /// Iterador que renderiza as páginas `indices` sob demanda, na ordem dada
+    pub fn pages(&self, indices: Vec<usize>, resolution: Resolution) -> impl Iterator<Item = Result<DynamicImage, Pdf2KeyError>> + use<'_, 'a> {
+        indices.into_iter().map(move |index| self.render_page(index, resolution))
+    }
+
+    /// Área da página que aparece na imagem renderizada com `resolution`
+    fn visible_area(&self, page: &PdfPage, index: usize, resolution: Resolution) -> VisibleArea {
+        // O tamanho pedido vale para a região visível (a metade do slide, a área sem margens)
+        let region = self.visible_region(page, index);
+        let scale = resolution.scale(region.width, region.height);
+
+        // Mesma área recortada por `render_page`; arredonda para não perder o último pixel do tamanho pedido
+        let pixels = |points: f32| (points * scale).round() as i32;
+        VisibleArea {
+            scale,
+            origin: crop_origin(page),
+            offset: (pixels(region.x), pixels(region.y)),
+            width: pixels(region.width),
+            height: pixels(region.height),
+        }
+    }
+
+    /// Região da página que vira o slide: a do corte das margens ou, sem ele, `slide_area`
+    fn visible_region(&self, page: &PdfPage, index: usize) -> PageRegion {
+        self.trim_regions.get(&index).copied().unwrap_or_else(|| self.slide_area(page, index))
+    }
+
+    /// A página inteira, a célula da apostila ou, em páginas divididas, a metade do slide
+    fn slide_area(&self, page: &PdfPage, index: usize) -> PageRegion {
+        if let Some(cell) = self.cell(index) {
+            return cell;
+        }
+        let (width, height) = (page.width().value, page.height().value);
+        match self.notes_side(page) {
+            Some(NotesSide::Right) => PageRegion { x: 0.0, y: 0.0, width: width / 2.0, height },
+            Some(NotesSide::Left) => PageRegion { x: width / 2.0, y: 0.0, width: width / 2.0, height },
+            None => PageRegion { x: 0.0, y: 0.0, width, height },
+        }
+    }
+
+    /// Lado das notas, se esta página deve ser dividida
+    fn notes_side(&self, page: &PdfPage) -> Option<NotesSide> {
+        match self.beamer_notes {
+            BeamerNotes::Off => None,
+            BeamerNotes::Right => Some(NotesSide::Right),
+            BeamerNotes::Left => Some(NotesSide::Left),
+            BeamerNotes::Auto => {
+                let aspect = page.width().value / page.height().value.max(1.0);
+                (aspect >= DOUBLE_WIDTH_ASPECT).then_some(NotesSide::Right)
+            }
+        }
+    }
+
+    /// Célula da apostila que vira o slide `index`, se a página foi dividida
+    fn cell(&self, index: usize) -> Option<PageRegion> {
+        self.views.get(index).and_then(|view| view.cell)
+    }
+
+    /// Primeiro índice que mostra a página `page` (na numeração dos PDFs juntados); `None`
+    /// se ela ficou fora das apostilas divididas
+    pub fn view_of(&self, page: usize) -> Option<usize> {
+        match self.views.is_empty() {
+            true => Some(page),
+            false => self.views.iter().position(|view| view.page == page),
+        }
+    }
+
+    /// Página (na numeração dos PDFs juntados) mostrada no índice `index`: a própria `index`
+    /// se nenhuma página foi dividida
+    pub fn source_page(&self, index: usize) -> usize {
+        self.views.get(index).map_or(index, |view| view.page)
+    }
+
+    /// PDF (entre os juntados) e página nele mostrados no índice `index`
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        let mut page = match self.views.get(index) {
+            Some(view) => view.page,
+            None if self.views.is_empty() => index,
+            None => return None,
+        };
+        for (source, document) in self.documents.iter().enumerate() {
+            let count = document.pages().len() as usize;
+            if page < count {
+                return Some((source, page));
+            }
+            page -= count;
+        }
+        None
+    }
+
+    /// Índice da primeira página do PDF `source` na numeração dos PDFs juntados
+    fn first_page(&self, source: usize) -> usize {
+        self.documents[..source].iter().map(|document| document.pages().len() as usize).sum()
+    }
+
+    fn page(&self, index: usize) -> Result<PdfPage<'_>, Pdf2KeyError> {
+        let (source, page) = self
+            .locate(index)
+            .ok_or_else(|| Pdf2KeyError::PageRender { page: index + 1, reason: "página fora do documento".to_string() })?;
+        self.documents[source]
+            .pages()
+            .get(page as u16)
+            .map_err(|e| Pdf2KeyError::PageRender { page: index + 1, reason: e.to_string() })
+    }
+}
+
+/// Percorre `first` e seus irmãos em pré-ordem, descendo nos filhos
+fn collect_bookmarks(first: Option<PdfBookmark>, level: usize, entries: &mut Vec<OutlineEntry>, visited: &mut usize) {
+    let mut current = first;
+    while let Some(bookmark) = current {
+        *visited += 1;
+        if *visited > MAX_OUTLINE_ENTRIES {
+            return;
+        }
+
+        // O destino fica no próprio bookmark ou numa ação "ir para" deste documento
+        let page = bookmark
+            .destination()
+            .and_then(|d| d.page_index().ok())
+            .or_else(|| {
+                let action = bookmark.action()?;
+                let destination = action.as_local_destination_action()?.destination().ok()?;
+                destination.page_index().ok()
+            });
+        let title = bookmark.title().map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        if let (Some(title), Some(page)) = (title, page) {
+            entries.push(OutlineEntry { title, page: page as usize, level });
+        }
+
+        collect_bookmarks(bookmark.first_child(), level + 1, entries, visited);
+        current = bookmark.next_sibling();
+    }
+}
+
+/// Canto superior esquerdo da CropBox (a área que o PDFium renderiza), em coordenadas do PDF
+fn crop_origin(page: &PdfPage) -> (f32, f32) {
+    let origin = page.boundaries().crop().map(|crop| (crop.bounds.left().value, crop.bounds.top().value));
+    origin.unwrap_or((0.0, page.height().value))
+}
+
+/// Metade da página com as notas do Beamer, a partir de `origin` (canto superior esquerdo da CropBox)
+fn notes_rect(side: NotesSide, origin: (f32, f32), width: f32, height: f32) -> PdfRect {
+    let (left, top) = origin;
+    let middle = left + width / 2.0;
+    match side {
+        NotesSide::Right => PdfRect::new_from_values(top - height, middle, top, left + width),
+        NotesSide::Left => PdfRect::new_from_values(top - height, left, top, middle),
+    }
+}
+
+/// Retângulo do PDF (coordenadas da página) como região a partir do canto superior esquerdo da CropBox
+fn page_region(page: &PdfPage, rect: &PdfRect) -> PageRegion {
+    let (left, top) = crop_origin(page);
+    PageRegion {
+        x: rect.left().value - left,
+        y: top - rect.top().value,
+        width: rect.width().value,
+        height: rect.height().value,
+    }
+}
+
+/// Região da página (a partir do canto superior esquerdo da CropBox) como retângulo do PDF
+fn pdf_rect(page: &PdfPage, region: &PageRegion) -> PdfRect {
+    let (left, top) = crop_origin(page);
+    PdfRect::new_from_values(
+        top - region.y - region.height,
+        left + region.x,
+        top - region.y,
+        left + region.x + region.width,
+    )
+}
+
+/// Retângulo do objeto em pixels da imagem, se ele aparece na área visível
+fn object_rect(object: &PdfPageObject, area: &VisibleArea) -> Option<PixelRect> {
+    object.bounds().ok().and_then(|bounds| area.to_pixels(&bounds.to_rect()))
+}
+
+/// O que entra no SVG e onde está o objeto
+#[derive(Clone, Copy)]
+struct SvgContent {
+    texts: bool,
+    images: bool,
+    /// PDF da página, entre os juntados
+    source: usize,
+    /// Dentro de um XObject de formulário
+    nested: bool,
+    /// Transformação acumulada dos formulários em volta
+    outer: Matrix,
+}
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+fn to_matrix(m: &PdfMatrix) -> Matrix {
+    [m.a(), m.b(), m.c(), m.d(), m.e(), m.f()]
+}
+
+/// Trechos de um caminho (ou recorte); `None` se algum for de tipo desconhecido
+fn path_segments<'b>(segments: &impl PdfPathSegments<'b>) -> Option<Vec<Segment>> {
+    let mut result = Vec::with_capacity(segments.len() as usize);
+    for i in 0..segments.len() {
+        let segment = segments.get(i).ok()?;
+        let (x, y) = (segment.x().value, segment.y().value);
+        result.push(match segment.segment_type() {
+            PdfPathSegmentType::MoveTo => Segment::MoveTo(x, y),
+            PdfPathSegmentType::LineTo => Segment::LineTo(x, y),
+            PdfPathSegmentType::BezierTo => Segment::BezierTo(x, y),
+            PdfPathSegmentType::Unknown => return None,
+        });
+        if segment.is_close() {
+            result.push(Segment::Close);
+        }
+    }
+    Some(result)
+}
+
+/// Conversão de coordenadas do PDF para pixels da imagem renderizada
+struct VisibleArea {
+    /// Pixels por ponto
+    scale: f32,
+    /// Canto superior esquerdo da CropBox, em coordenadas do PDF
+    origin: (f32, f32),
+    /// Pixels cortados à esquerda e em cima (metade das notas, margens)
+    offset: (i32, i32),
+    width: i32,
+    height: i32,
+}
+
+impl VisibleArea {
+    /// Retângulo em pixels, recortado à área visível; `None` se ficar vazio
+    fn to_pixels(&self, rect: &PdfRect) -> Option<PixelRect> {
+        // PDF: origem embaixo à esquerda; imagem: em cima à esquerda
+        let (origin_x, origin_y) = self.origin;
+        let x = |value: f32| (((value - origin_x) * self.scale) as i32 - self.offset.0).clamp(0, self.width);
+        let y = |value: f32| (((origin_y - value) * self.scale) as i32 - self.offset.1).clamp(0, self.height);
+        let (left, right) = (x(rect.left().value), x(rect.right().value));
+        let (top, bottom) = (y(rect.top().value), y(rect.bottom().value));
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some(PixelRect {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+    }
+
+    /// Retângulo em pixels da imagem como região da página, em pontos
+    fn to_region(&self, rect: &PixelRect) -> PageRegion {
+        PageRegion {
+            x: (self.offset.0 + rect.x as i32) as f32 / self.scale,
+            y: (self.offset.1 + rect.y as i32) as f32 / self.scale,
+            width: rect.width as f32 / self.scale,
+            height: rect.height as f32 / self.scale,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NotesSide {
+    Left,
+    Right,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(rect: PdfRect) -> (f32, f32, f32, f32) {
+        (rect.bottom().value, rect.left().value, rect.top().value, rect.right().value)
+    }
+
+    #[test]
+    fn notes_half_follows_crop_origin() {
+        assert_eq!(values(notes_rect(NotesSide::Right, (0.0, 300.0), 800.0, 300.0)), (0.0, 400.0, 300.0, 800.0));
+        // CropBox deslocada: [50 20 850 320]
+        assert_eq!(values(notes_rect(NotesSide::Right, (50.0, 320.0), 800.0, 300.0)), (20.0, 450.0, 320.0, 850.0));
+        assert_eq!(values(notes_rect(NotesSide::Left, (50.0, 320.0), 800.0, 300.0)), (20.0, 50.0, 320.0, 450.0));
     }
 }
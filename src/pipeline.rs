@@ -0,0 +1,102 @@
+//! Pipeline de páginas: produção sequencial + processamento paralelo
+//! O PDFium não é thread-safe, então a renderização fica na thread chamadora
+//! e apenas a codificação/pós-processamento vai para o pool de workers.
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+/// Número padrão de workers: um por núcleo disponível
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Processa itens produzidos em sequência usando `jobs` workers em paralelo
+///
+/// `source` é consumido na thread chamadora (ex: renderização no PDFium); cada item
+/// é entregue a `work` em um worker. No máximo `jobs` itens aguardam na fila, então
+/// a memória fica limitada a cerca de `2 * jobs` itens em trânsito. `on_done` recebe
+/// a posição do item concluído e o total já concluído. Os resultados voltam na ordem original.
+///
+/// Na primeira falha (da produção ou de um worker) a produção para e o erro é retornado.
+pub fn run_ordered<T, R, E, S, W, P>(source: S, jobs: usize, work: W, mut on_done: P) -> Result<Vec<R>, E>
+where
+    T: Send,
+    R: Send,
+    E: Send,
+    S: Iterator<Item = Result<T, E>>,
+    W: Fn(usize, T) -> Result<R, E> + Sync,
+    P: FnMut(usize, usize),
+{
+    let jobs = jobs.max(1);
+    let (work_tx, work_rx) = mpsc::sync_channel::<(usize, T)>(jobs);
+    let work_rx = Mutex::new(work_rx);
+    let (done_tx, done_rx) = mpsc::channel::<(usize, Result<R, E>)>();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let done_tx = done_tx.clone();
+            let work_rx = &work_rx;
+            let work = &work;
+            scope.spawn(move || loop {
+                // O lock só é mantido enquanto espera o próximo item
+                let next = work_rx.lock().unwrap().recv();
+                let Ok((index, item)) = next else { break };
+                if done_tx.send((index, work(index, item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(done_tx);
+
+        let mut results: Vec<Option<R>> = Vec::new();
+        let mut error = None;
+        let mut completed = 0;
+
+        let mut collect = |index: usize, result: Result<R, E>, error: &mut Option<E>| match result {
+            Ok(value) => {
+                if results.len() <= index {
+                    results.resize_with(index + 1, || None);
+                }
+                results[index] = Some(value);
+                completed += 1;
+                on_done(index, completed);
+            }
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        };
+
+        for (index, item) in source.enumerate() {
+            match item {
+                Ok(item) => {
+                    if work_tx.send((index, item)).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+
+            // Recolhe o que já terminou, sem bloquear a produção
+            while let Ok((index, result)) = done_rx.try_recv() {
+                collect(index, result, &mut error);
+            }
+            if error.is_some() {
+                break;
+            }
+        }
+        drop(work_tx);
+
+        for (index, result) in done_rx {
+            collect(index, result, &mut error);
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(results.into_iter().flatten().collect()),
+        }
+    })
+}
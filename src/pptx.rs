@@ -0,0 +1,647 @@
+//! Módulo para geração de apresentações PowerPoint (.pptx)
+//! Escreve o pacote OOXML diretamente, sem depender do PowerPoint
+
+use crate::editable::{SlideObject, TextBox};
+use crate::layout::{PixelMapping, SlideLayout};
+use crate::links::{PixelRect, SlideLink, SlideLinkTarget};
+use crate::slide::Slide;
+use crate::xml;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// EMUs por polegada (unidade do DrawingML)
+const EMU_PER_INCH: f64 = 914_400.0;
+
+/// Largura padrão do slide: 13,333" (16:9 do PowerPoint)
+const SLIDE_WIDTH_EMU: f64 = 12_192_000.0;
+
+/// Limites aceitos pelo PowerPoint para as dimensões do slide (1" a 56")
+const MIN_SLIDE_EMU: f64 = EMU_PER_INCH;
+const MAX_SLIDE_EMU: f64 = 56.0 * EMU_PER_INCH;
+
+/// EMUs por ponto tipográfico (tamanho das fontes)
+const EMU_PER_POINT: f64 = 12_700.0;
+
+/// Nome da seção dos slides que vêm antes da primeira seção do sumário
+const DEFAULT_SECTION: &str = "Seção padrão";
+
+/// Cria apresentações PowerPoint com uma imagem por slide
+pub struct PptxBuilder {
+    slides: Vec<Slide>,
+    layout: SlideLayout,
+}
+
+impl Default for PptxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PptxBuilder {
+    /// Cria um novo builder para apresentações PowerPoint
+    pub fn new() -> Self {
+        Self {
+            slides: Vec::new(),
+            layout: SlideLayout::default(),
+        }
+    }
+
+    /// Proporção dos slides, encaixe das páginas e cor de fundo
+    ///
+    /// Com `FitMode::Crop` a imagem é recortada (`srcRect`) e continua inteira no arquivo.
+    pub fn with_layout(mut self, layout: SlideLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Adiciona uma imagem como um novo slide
+    pub fn add_slide(&mut self, image_path: &Path) {
+        self.push_slide(Slide::new(image_path));
+    }
+
+    /// Adiciona um slide com notas e camadas de build (cada camada aparece com um clique)
+    pub fn push_slide(&mut self, slide: Slide) {
+        self.slides.push(slide);
+    }
+
+    /// Constrói e salva o arquivo .pptx
+    pub fn build(&self, output_path: &Path) -> Result<()> {
+        self.build_with_progress(output_path, &mut |_| Ok(()))
+    }
+
+    /// Constrói e salva o arquivo .pptx, chamando `on_slide` com o número de slides já gravados
+    ///
+    /// Um erro retornado por `on_slide` interrompe a geração (ex: cancelamento).
+    pub fn build_with_progress(&self, output_path: &Path, on_slide: &mut dyn FnMut(usize) -> Result<()>) -> Result<()> {
+        if self.slides.is_empty() {
+            anyhow::bail!("Nenhum slide foi adicionado");
+        }
+
+        // Com `FitMode::MatchPage` o slide segue a proporção da primeira página
+        let first = image::image_dimensions(&self.slides[0].image)
+            .context("Falha ao ler imagem do primeiro slide")?;
+        let aspect = self.layout.aspect(first);
+        let (slide_cx, slide_cy) = slide_size_emu(aspect);
+
+        let file = File::create(output_path).context("Falha ao criar o arquivo .pptx")?;
+        let mut zip = ZipWriter::new(file);
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+        let slide_count = self.slides.len();
+        // Slides com notas ganham um notesSlide; o notesMaster só existe se houver algum
+        let notes_slides: Vec<usize> = (1..=slide_count).filter(|n| !self.slides[n - 1].notes.is_empty()).collect();
+        let has_notes = !notes_slides.is_empty();
+
+        zip.start_file("[Content_Types].xml", deflated)?;
+        zip.write_all(content_types(slide_count, &notes_slides).as_bytes())?;
+
+        zip.start_file("_rels/.rels", deflated)?;
+        zip.write_all(ROOT_RELS.as_bytes())?;
+
+        zip.start_file("docProps/app.xml", deflated)?;
+        zip.write_all(app_props(slide_count).as_bytes())?;
+
+        zip.start_file("docProps/core.xml", deflated)?;
+        zip.write_all(CORE_PROPS.as_bytes())?;
+
+        zip.start_file("ppt/presentation.xml", deflated)?;
+        zip.write_all(presentation(slide_count, slide_cx, slide_cy, has_notes, &sections(&self.slides)).as_bytes())?;
+
+        zip.start_file("ppt/_rels/presentation.xml.rels", deflated)?;
+        zip.write_all(presentation_rels(slide_count, has_notes).as_bytes())?;
+
+        for (name, content) in [
+            ("ppt/presProps.xml", PRES_PROPS),
+            ("ppt/viewProps.xml", VIEW_PROPS),
+            ("ppt/tableStyles.xml", TABLE_STYLES),
+            ("ppt/theme/theme1.xml", THEME),
+            ("ppt/slideMasters/slideMaster1.xml", SLIDE_MASTER),
+            ("ppt/slideMasters/_rels/slideMaster1.xml.rels", SLIDE_MASTER_RELS),
+            ("ppt/slideLayouts/slideLayout1.xml", SLIDE_LAYOUT),
+            ("ppt/slideLayouts/_rels/slideLayout1.xml.rels", SLIDE_LAYOUT_RELS),
+        ] {
+            zip.start_file(name, deflated)?;
+            zip.write_all(content.as_bytes())?;
+        }
+
+        if has_notes {
+            for (name, content) in [
+                ("ppt/theme/theme2.xml", THEME),
+                ("ppt/notesMasters/notesMaster1.xml", NOTES_MASTER),
+                ("ppt/notesMasters/_rels/notesMaster1.xml.rels", NOTES_MASTER_RELS),
+            ] {
+                zip.start_file(name, deflated)?;
+                zip.write_all(content.as_bytes())?;
+            }
+        }
+
+        for (i, current) in self.slides.iter().enumerate() {
+            let n = i + 1;
+            let extension = image_extension(&current.image);
+
+            zip.start_file(format!("ppt/media/image{}.{}", n, extension), stored)?;
+            zip.write_all(&std::fs::read(&current.image)?)?;
+
+            if let Some(vector) = &current.vector {
+                zip.start_file(format!("ppt/media/image{}.svg", n), deflated)?;
+                zip.write_all(&std::fs::read(vector)?)?;
+            }
+
+            for (k, layer) in current.builds.iter().enumerate() {
+                zip.start_file(format!("ppt/media/image{}_build{}.png", n, k + 1), stored)?;
+                zip.write_all(&std::fs::read(layer)?)?;
+            }
+
+            for (k, object) in current.objects.iter().enumerate() {
+                if let SlideObject::Image(placed) = object {
+                    zip.start_file(format!("ppt/media/image{}_obj{}.{}", n, k + 1, image_extension(&placed.path)), stored)?;
+                    zip.write_all(&std::fs::read(&placed.path)?)?;
+                }
+            }
+
+            let size = image::image_dimensions(&current.image)
+                .context(format!("Falha ao ler imagem do slide {}", n))?;
+            let frame = PageFrame::new(&self.layout, aspect, size, (slide_cx, slide_cy));
+
+            zip.start_file(format!("ppt/slides/slide{}.xml", n), deflated)?;
+            zip.write_all(slide(n, current, &frame, self.layout.background).as_bytes())?;
+
+            let notes = &current.notes;
+            zip.start_file(format!("ppt/slides/_rels/slide{}.xml.rels", n), deflated)?;
+            zip.write_all(slide_rels(n, extension, current, !notes.is_empty()).as_bytes())?;
+
+            if !notes.is_empty() {
+                zip.start_file(format!("ppt/notesSlides/notesSlide{}.xml", n), deflated)?;
+                zip.write_all(notes_slide(notes).as_bytes())?;
+
+                zip.start_file(format!("ppt/notesSlides/_rels/notesSlide{}.xml.rels", n), deflated)?;
+                zip.write_all(notes_slide_rels(n).as_bytes())?;
+            }
+
+            on_slide(n)?;
+        }
+
+        zip.finish()?;
+
+        log::info!("Apresentação PowerPoint criada: {}", output_path.display());
+        Ok(())
+    }
+}
+
+/// Converte a proporção do slide (largura / altura) em dimensões de slide (EMU)
+fn slide_size_emu(aspect: f64) -> (u64, u64) {
+    let aspect = 1.0 / aspect.max(f64::EPSILON);
+    let cy = (SLIDE_WIDTH_EMU * aspect).clamp(MIN_SLIDE_EMU, MAX_SLIDE_EMU);
+    // Páginas muito altas: fixa a altura máxima e reduz a largura
+    let cx = if SLIDE_WIDTH_EMU * aspect > MAX_SLIDE_EMU {
+        (cy / aspect).max(MIN_SLIDE_EMU)
+    } else {
+        SLIDE_WIDTH_EMU
+    };
+    (cx.round() as u64, cy.round() as u64)
+}
+
+/// Posição da imagem da página (e das camadas de build) num slide
+struct PageFrame {
+    /// Parte visível da imagem em EMU: (x, y, largura, altura)
+    rect: (i64, i64, u64, u64),
+    /// Frações da imagem cortadas pelas bordas do slide (esquerda, topo, direita, base)
+    crop: (f64, f64, f64, f64),
+    /// Pixels da imagem em EMU, para objetos e links
+    mapping: PixelMapping,
+}
+
+impl PageFrame {
+    fn new(layout: &SlideLayout, aspect: f64, image: (u32, u32), (cx, cy): (u64, u64)) -> Self {
+        let placement = layout.place(aspect, image);
+        let mapping = placement.mapping((cx as f64, cy as f64), image);
+        let (x, y, w, h) = mapping.image(image);
+        // O que passa das bordas fica de fora do quadro e é cortado da imagem
+        let (left, top) = (x.max(0.0), y.max(0.0));
+        let (right, bottom) = ((x + w).min(cx as f64), (y + h).min(cy as f64));
+        Self {
+            rect: (left.round() as i64, top.round() as i64, (right - left).round() as u64, (bottom - top).round() as u64),
+            crop: placement.cropped(),
+            mapping,
+        }
+    }
+}
+
+fn image_extension(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+        Some(e) if e == "jpg" || e == "jpeg" => "jpeg",
+        Some(e) if e == "webp" => "webp",
+        _ => "png",
+    }
+}
+
+fn content_types(slide_count: usize, notes_slides: &[usize]) -> String {
+    let mut overrides = String::new();
+    for n in 1..=slide_count {
+        overrides.push_str(&format!(
+            r#"<Override PartName="/ppt/slides/slide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>"#,
+            n
+        ));
+    }
+    if !notes_slides.is_empty() {
+        overrides.push_str(r#"<Override PartName="/ppt/notesMasters/notesMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesMaster+xml"/><Override PartName="/ppt/theme/theme2.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#);
+    }
+    for n in notes_slides {
+        overrides.push_str(&format!(
+            r#"<Override PartName="/ppt/notesSlides/notesSlide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesSlide+xml"/>"#,
+            n
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/><Default Extension="jpeg" ContentType="image/jpeg"/><Default Extension="svg" ContentType="image/svg+xml"/><Default Extension="webp" ContentType="image/webp"/><Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/><Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/><Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/><Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/><Override PartName="/ppt/presProps.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presProps+xml"/><Override PartName="/ppt/viewProps.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.viewProps+xml"/><Override PartName="/ppt/tableStyles.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.tableStyles+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/><Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>{}</Types>"#,
+        overrides
+    )
+}
+
+/// Seções do PowerPoint: título e slides (1-based) de cada uma
+///
+/// Slides antes da primeira seção ficam numa seção padrão, já que o PowerPoint exige
+/// que todos pertençam a alguma quando há seções.
+fn sections(slides: &[Slide]) -> Vec<(String, Vec<usize>)> {
+    let mut sections: Vec<(String, Vec<usize>)> = Vec::new();
+    if slides.iter().all(|s| s.section.is_none()) {
+        return sections;
+    }
+    for (i, slide) in slides.iter().enumerate() {
+        match (&slide.section, sections.last_mut()) {
+            (None, Some(section)) => section.1.push(i + 1),
+            (Some(title), _) => sections.push((title.clone(), vec![i + 1])),
+            (None, None) => sections.push((DEFAULT_SECTION.to_string(), vec![i + 1])),
+        }
+    }
+    sections
+}
+
+fn presentation(slide_count: usize, cx: u64, cy: u64, has_notes: bool, sections: &[(String, Vec<usize>)]) -> String {
+    let mut slide_ids = String::new();
+    for n in 1..=slide_count {
+        // rId1..5 = master, props e tema; rId6 = notesMaster; slides a partir de rId10
+        slide_ids.push_str(&format!(r#"<p:sldId id="{}" r:id="rId{}"/>"#, 255 + n, 9 + n));
+    }
+    let notes_master = if has_notes {
+        r#"<p:notesMasterIdLst><p:notesMasterId r:id="rId6"/></p:notesMasterIdLst>"#
+    } else {
+        ""
+    };
+    // Extensão do PowerPoint 2010 (p14); os ids das seções só precisam ser GUIDs únicos
+    let section_list = if sections.is_empty() {
+        String::new()
+    } else {
+        let entries: String = sections
+            .iter()
+            .enumerate()
+            .map(|(i, (title, slides))| {
+                let ids: String = slides.iter().map(|n| format!(r#"<p14:sldId id="{}"/>"#, 255 + n)).collect();
+                format!(
+                    r#"<p14:section name="{}" id="{{5EC7D1A0-0000-4000-8000-{:012X}}}"><p14:sldIdLst>{}</p14:sldIdLst></p14:section>"#,
+                    xml::escape(title),
+                    i + 1,
+                    ids
+                )
+            })
+            .collect();
+        format!(
+            r#"<p:extLst><p:ext uri="{{521415D9-36F7-43E2-AB2F-B90AF26B5E84}}"><p14:sectionLst xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main">{}</p14:sectionLst></p:ext></p:extLst>"#,
+            entries
+        )
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentation xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" saveSubsetFonts="1"><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst>{notes_master}<p:sldIdLst>{slide_ids}</p:sldIdLst><p:sldSz cx="{cx}" cy="{cy}"/><p:notesSz cx="6858000" cy="9144000"/><p:defaultTextStyle><a:defPPr><a:defRPr lang="pt-BR"/></a:defPPr></p:defaultTextStyle>{section_list}</p:presentation>"#,
+        section_list = section_list,
+        notes_master = notes_master,
+        slide_ids = slide_ids,
+        cx = cx,
+        cy = cy
+    )
+}
+
+fn presentation_rels(slide_count: usize, has_notes: bool) -> String {
+    let mut rels = String::from(
+        r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="slideMasters/slideMaster1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/presProps" Target="presProps.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/viewProps" Target="viewProps.xml"/><Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/><Relationship Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/tableStyles" Target="tableStyles.xml"/>"#,
+    );
+    if has_notes {
+        rels.push_str(r#"<Relationship Id="rId6" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="notesMasters/notesMaster1.xml"/>"#);
+    }
+    for n in 1..=slide_count {
+        rels.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{}.xml"/>"#,
+            9 + n,
+            n
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}</Relationships>"#,
+        rels
+    )
+}
+
+/// Slide com a imagem da página, as camadas de build por cima (cada uma entrando com
+/// um clique), os objetos editáveis e, no topo, as áreas transparentes dos links
+///
+/// `frame` posiciona a imagem e converte pixels da imagem em EMU; `background` aparece nas faixas.
+fn slide(n: usize, current: &Slide, frame: &PageFrame, background: [u8; 3]) -> String {
+    let (builds, objects, title) = (current.builds.len(), current.objects.len(), current.title.as_str());
+    // Imagem da página: id 2, rId2 (SVG: rIdSvg); camada k: id 2 + k, rId(10 + k);
+    // objeto k: rIdObj{k}; link k: rIdLink{k}
+    let svg = current.vector.as_ref().map(|_| "rIdSvg");
+    let crop = Some(frame.crop);
+    let mut pictures = picture(2, &format!("Slide {}", n), ("rId2", svg), frame.rect, crop);
+    for k in 1..=builds {
+        pictures.push_str(&picture(2 + k, &format!("Build {}", k), (&format!("rId{}", 10 + k), None), frame.rect, crop));
+    }
+    for (k, object) in current.objects.iter().enumerate() {
+        let id = 3 + builds + k;
+        pictures.push_str(&match object {
+            SlideObject::Image(placed) => {
+                picture(id, &format!("Imagem {}", k + 1), (&format!("rIdObj{}", k + 1), None), emu(&placed.rect, &frame.mapping), None)
+            }
+            SlideObject::Text(text) => text_shape(id, k + 1, text, &frame.mapping),
+        });
+    }
+    for (k, link) in current.links.iter().enumerate() {
+        pictures.push_str(&link_shape(3 + builds + objects + k, k + 1, link, &frame.mapping));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld{name}><p:bg><p:bgPr><a:solidFill><a:srgbClr val="{r:02X}{g:02X}{b:02X}"/></a:solidFill><a:effectLst/></p:bgPr></p:bg><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>{pictures}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr>{timing}</p:sld>"#,
+        name = if title.is_empty() { String::new() } else { format!(r#" name="{}""#, xml::escape(title)) },
+        r = background[0],
+        g = background[1],
+        b = background[2],
+        pictures = pictures,
+        timing = build_timing(builds)
+    )
+}
+
+/// Retângulo sem preenchimento nem borda com o hyperlink
+fn link_shape(id: usize, k: usize, link: &SlideLink, mapping: &PixelMapping) -> String {
+    let action = match link.target {
+        SlideLinkTarget::Slide(_) => r#" action="ppaction://hlinksldjump""#,
+        SlideLinkTarget::Url(_) => "",
+    };
+    let (x, y, w, h) = emu(&link.rect, mapping);
+    format!(
+        r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="Link {k}"><a:hlinkClick r:id="rIdLink{k}"{action}/></p:cNvPr><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{w}" cy="{h}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/><a:ln><a:noFill/></a:ln></p:spPr></p:sp>"#,
+        id = id,
+        k = k,
+        action = action,
+        x = x,
+        y = y,
+        w = w,
+        h = h
+    )
+}
+
+/// Caixa de texto de uma linha, sem margens internas, na posição do texto no PDF
+fn text_shape(id: usize, k: usize, text: &TextBox, mapping: &PixelMapping) -> String {
+    let (x, y, w, h) = emu(&text.rect, mapping);
+    // Tamanho em centésimos de ponto, nos limites aceitos pelo PowerPoint
+    let size = (text.size as f64 * mapping.scale.1 / EMU_PER_POINT * 100.0).round().clamp(100.0, 400_000.0) as u32;
+    let [r, g, b] = text.color;
+    format!(
+        r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="Texto {k}"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{w}" cy="{h}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/></p:spPr><p:txBody><a:bodyPr wrap="none" lIns="0" tIns="0" rIns="0" bIns="0" rtlCol="0"><a:noAutofit/></a:bodyPr><a:lstStyle/><a:p><a:r><a:rPr lang="pt-BR" sz="{size}" b="{bold}" i="{italic}" dirty="0"><a:solidFill><a:srgbClr val="{r:02X}{g:02X}{b:02X}"/></a:solidFill><a:latin typeface="{font}"/><a:cs typeface="{font}"/></a:rPr><a:t>{text}</a:t></a:r></a:p></p:txBody></p:sp>"#,
+        id = id,
+        k = k,
+        x = x,
+        y = y,
+        w = w,
+        h = h,
+        size = size,
+        bold = text.bold as u8,
+        italic = text.italic as u8,
+        r = r,
+        g = g,
+        b = b,
+        font = xml::escape(&text.font),
+        text = xml::escape(&text.text)
+    )
+}
+
+/// `embed` é a imagem e, opcionalmente, a versão SVG (o PowerPoint desenha o SVG e versões
+/// sem suporte usam a imagem); `off` e `ext` em EMU: (x, y, largura, altura). `crop` são as
+/// frações cortadas da imagem (esquerda, topo, direita, base).
+fn picture(
+    id: usize,
+    name: &str,
+    (embed, svg): (&str, Option<&str>),
+    (x, y, cx, cy): (i64, i64, u64, u64),
+    crop: Option<(f64, f64, f64, f64)>,
+) -> String {
+    let svg = match svg {
+        Some(svg) => format!(
+            r#"<a:extLst><a:ext uri="{{96DAC541-7B7A-43D3-8B79-37D633B846F1}}"><asvg:svgBlip xmlns:asvg="http://schemas.microsoft.com/office/drawing/2016/SVG/main" r:embed="{}"/></a:ext></a:extLst>"#,
+            svg
+        ),
+        None => String::new(),
+    };
+    // srcRect em milésimos de porcentagem
+    let src_rect = match crop {
+        Some((l, t, r, b)) if l + t + r + b > 0.0 => {
+            let per_mille = |v: f64| (v * 100_000.0).round() as i64;
+            format!(r#"<a:srcRect l="{}" t="{}" r="{}" b="{}"/>"#, per_mille(l), per_mille(t), per_mille(r), per_mille(b))
+        }
+        _ => String::new(),
+    };
+    format!(
+        r#"<p:pic><p:nvPicPr><p:cNvPr id="{id}" name="{name}"/><p:cNvPicPr><a:picLocks noChangeAspect="1"/></p:cNvPicPr><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="{embed}">{svg}</a:blip>{src_rect}<a:stretch><a:fillRect/></a:stretch></p:blipFill><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr></p:pic>"#,
+        id = id,
+        name = name,
+        embed = embed,
+        svg = svg,
+        src_rect = src_rect,
+        x = x,
+        y = y,
+        cx = cx,
+        cy = cy
+    )
+}
+
+/// Retângulo em pixels da imagem convertido em EMU: (x, y, largura, altura)
+///
+/// A posição pode ser negativa quando a página é cortada pelas bordas do slide.
+fn emu(rect: &PixelRect, mapping: &PixelMapping) -> (i64, i64, u64, u64) {
+    let (x, y, w, h) = mapping.rect(rect);
+    (x.round() as i64, y.round() as i64, w.round() as u64, h.round() as u64)
+}
+
+/// Animações de entrada ("Aparecer", ao clicar) para as camadas de build
+fn build_timing(builds: usize) -> String {
+    if builds == 0 {
+        return String::new();
+    }
+    let mut clicks = String::new();
+    // ids 1 e 2 são a raiz e a sequência principal; cada clique usa 4 nós
+    for k in 1..=builds {
+        let id = 3 + (k - 1) * 4;
+        clicks.push_str(&format!(
+            r#"<p:par><p:cTn id="{a}" fill="hold"><p:stCondLst><p:cond delay="indefinite"/></p:stCondLst><p:childTnLst><p:par><p:cTn id="{b}" fill="hold"><p:stCondLst><p:cond delay="0"/></p:stCondLst><p:childTnLst><p:par><p:cTn id="{c}" presetID="1" presetClass="entr" presetSubtype="0" fill="hold" nodeType="clickEffect"><p:stCondLst><p:cond delay="0"/></p:stCondLst><p:childTnLst><p:set><p:cBhvr><p:cTn id="{d}" dur="1" fill="hold"><p:stCondLst><p:cond delay="0"/></p:stCondLst></p:cTn><p:tgtEl><p:spTgt spid="{spid}"/></p:tgtEl><p:attrNameLst><p:attrName>style.visibility</p:attrName></p:attrNameLst></p:cBhvr><p:to><p:strVal val="visible"/></p:to></p:set></p:childTnLst></p:cTn></p:par></p:childTnLst></p:cTn></p:par></p:childTnLst></p:cTn></p:par>"#,
+            a = id,
+            b = id + 1,
+            c = id + 2,
+            d = id + 3,
+            spid = 2 + k
+        ));
+    }
+    format!(
+        r#"<p:timing><p:tnLst><p:par><p:cTn id="1" dur="indefinite" restart="never" nodeType="tmRoot"><p:childTnLst><p:seq concurrent="1" nextAc="seek"><p:cTn id="2" dur="indefinite" nodeType="mainSeq"><p:childTnLst>{}</p:childTnLst></p:cTn><p:prevCondLst><p:cond evt="onPrev" delay="0"><p:tgtEl><p:sldTgt/></p:tgtEl></p:cond></p:prevCondLst><p:nextCondLst><p:cond evt="onNext" delay="0"><p:tgtEl><p:sldTgt/></p:tgtEl></p:cond></p:nextCondLst></p:seq></p:childTnLst></p:cTn></p:par></p:tnLst></p:timing>"#,
+        clicks
+    )
+}
+
+fn slide_rels(n: usize, extension: &str, current: &Slide, has_notes: bool) -> String {
+    let builds = current.builds.len();
+    let notes = if has_notes {
+        format!(
+            r#"<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{}.xml"/>"#,
+            n
+        )
+    } else {
+        String::new()
+    };
+    let mut layers = String::new();
+    for k in 1..=builds {
+        layers.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}_build{}.png"/>"#,
+            10 + k,
+            n,
+            k
+        ));
+    }
+    if current.vector.is_some() {
+        layers.push_str(&format!(
+            r#"<Relationship Id="rIdSvg" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.svg"/>"#,
+            n
+        ));
+    }
+    for (k, object) in current.objects.iter().enumerate() {
+        if let SlideObject::Image(placed) = object {
+            layers.push_str(&format!(
+                r#"<Relationship Id="rIdObj{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}_obj{}.{}"/>"#,
+                k + 1,
+                n,
+                k + 1,
+                image_extension(&placed.path)
+            ));
+        }
+    }
+    for (k, link) in current.links.iter().enumerate() {
+        layers.push_str(&match &link.target {
+            SlideLinkTarget::Url(url) => format!(
+                r#"<Relationship Id="rIdLink{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}" TargetMode="External"/>"#,
+                k + 1,
+                xml::escape(url)
+            ),
+            SlideLinkTarget::Slide(target) => format!(
+                r#"<Relationship Id="rIdLink{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slide{}.xml"/>"#,
+                k + 1,
+                target + 1
+            ),
+        });
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image{}.{}"/>{}{}</Relationships>"#,
+        n, extension, notes, layers
+    )
+}
+
+/// Página de notas: miniatura do slide e o texto das notas, um parágrafo por linha
+fn notes_slide(notes: &str) -> String {
+    let paragraphs: String = notes
+        .lines()
+        .map(|line| match line.trim_end() {
+            "" => String::from(r#"<a:p><a:endParaRPr lang="pt-BR"/></a:p>"#),
+            line => format!(r#"<a:p><a:r><a:rPr lang="pt-BR"/><a:t>{}</a:t></a:r></a:p>"#, xml::escape(line)),
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notes xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr><p:sp><p:nvSpPr><p:cNvPr id="2" name="Slide Image Placeholder 1"/><p:cNvSpPr><a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/></p:cNvSpPr><p:nvPr><p:ph type="sldImg"/></p:nvPr></p:nvSpPr><p:spPr/></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Notes Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:lstStyle/>{}</p:txBody></p:sp></p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:notes>"#,
+        paragraphs
+    )
+}
+
+fn notes_slide_rels(n: usize) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="../notesMasters/notesMaster1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="../slides/slide{}.xml"/></Relationships>"#,
+        n
+    )
+}
+
+fn app_props(slide_count: usize) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes"><Application>PDF2Key</Application><Slides>{}</Slides><PresentationFormat>Custom</PresentationFormat></Properties>"#,
+        slide_count
+    )
+}
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/></Relationships>"#;
+
+const CORE_PROPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:creator>PDF2Key</dc:creator></cp:coreProperties>"#;
+
+const PRES_PROPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentationPr xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#;
+
+const VIEW_PROPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:viewPr xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:normalViewPr><p:restoredLeft sz="15620"/><p:restoredTop sz="94660"/></p:normalViewPr><p:gridSpacing cx="76200" cy="76200"/></p:viewPr>"#;
+
+const TABLE_STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:tblStyleLst xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" def="{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}"/>"#;
+
+const SLIDE_MASTER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:bg><p:bgRef idx="1001"><a:schemeClr val="bg1"/></p:bgRef></p:bg><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr></p:spTree></p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/><p:sldLayoutIdLst><p:sldLayoutId id="2147483649" r:id="rId1"/></p:sldLayoutIdLst><p:txStyles><p:titleStyle><a:lvl1pPr><a:defRPr sz="4400"/></a:lvl1pPr></p:titleStyle><p:bodyStyle><a:lvl1pPr><a:defRPr sz="2800"/></a:lvl1pPr></p:bodyStyle><p:otherStyle><a:lvl1pPr><a:defRPr sz="1800"/></a:lvl1pPr></p:otherStyle></p:txStyles></p:sldMaster>"#;
+
+const SLIDE_MASTER_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme1.xml"/></Relationships>"#;
+
+const SLIDE_LAYOUT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" type="blank" preserve="1"><p:cSld name="Blank"><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr></p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>"#;
+
+const SLIDE_LAYOUT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="../slideMasters/slideMaster1.xml"/></Relationships>"#;
+
+const NOTES_MASTER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notesMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:bg><p:bgRef idx="1001"><a:schemeClr val="bg1"/></p:bgRef></p:bg><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr><p:sp><p:nvSpPr><p:cNvPr id="2" name="Slide Image Placeholder 1"/><p:cNvSpPr><a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/></p:cNvSpPr><p:nvPr><p:ph type="sldImg" idx="2"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="685800" y="1143000"/><a:ext cx="5486400" cy="3086100"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/><a:ln w="12700"><a:solidFill><a:prstClr val="black"/></a:solidFill></a:ln></p:spPr></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Notes Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="body" sz="quarter" idx="3"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="685800" y="4400550"/><a:ext cx="5486400" cy="3600450"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr><p:txBody><a:bodyPr vert="horz" lIns="91440" tIns="45720" rIns="91440" bIns="45720" rtlCol="0"/><a:lstStyle/><a:p><a:endParaRPr lang="pt-BR"/></a:p></p:txBody></p:sp></p:spTree></p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/><p:notesStyle><a:lvl1pPr marL="0" algn="l" defTabSz="914400" rtl="0" eaLnBrk="1" latinLnBrk="0" hangingPunct="1"><a:defRPr sz="1200" kern="1200"><a:solidFill><a:schemeClr val="tx1"/></a:solidFill><a:latin typeface="+mn-lt"/><a:ea typeface="+mn-ea"/><a:cs typeface="+mn-cs"/></a:defRPr></a:lvl1pPr></p:notesStyle></p:notesMaster>"#;
+
+const NOTES_MASTER_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme2.xml"/></Relationships>"#;
+
+const THEME: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="PDF2Key"><a:themeElements><a:clrScheme name="PDF2Key"><a:dk1><a:srgbClr val="000000"/></a:dk1><a:lt1><a:srgbClr val="FFFFFF"/></a:lt1><a:dk2><a:srgbClr val="1F2937"/></a:dk2><a:lt2><a:srgbClr val="F1F5F9"/></a:lt2><a:accent1><a:srgbClr val="38BDF8"/></a:accent1><a:accent2><a:srgbClr val="0EA5E9"/></a:accent2><a:accent3><a:srgbClr val="4ADE80"/></a:accent3><a:accent4><a:srgbClr val="F87171"/></a:accent4><a:accent5><a:srgbClr val="94A3B8"/></a:accent5><a:accent6><a:srgbClr val="334155"/></a:accent6><a:hlink><a:srgbClr val="0563C1"/></a:hlink><a:folHlink><a:srgbClr val="954F72"/></a:folHlink></a:clrScheme><a:fontScheme name="PDF2Key"><a:majorFont><a:latin typeface="Calibri"/><a:ea typeface=""/><a:cs typeface=""/></a:majorFont><a:minorFont><a:latin typeface="Calibri"/><a:ea typeface=""/><a:cs typeface=""/></a:minorFont></a:fontScheme><a:fmtScheme name="PDF2Key"><a:fillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:fillStyleLst><a:lnStyleLst><a:ln w="6350"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="12700"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="19050"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln></a:lnStyleLst><a:effectStyleLst><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle></a:effectStyleLst><a:bgFillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:bgFillStyleLst></a:fmtScheme></a:themeElements><a:objectDefaults/><a:extraClrSchemeLst/></a:theme>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_group_following_slides() {
+        let slide = |section: Option<&str>| Slide { section: section.map(str::to_string), ..Slide::default() };
+        assert!(sections(&[slide(None), slide(None)]).is_empty());
+        assert_eq!(
+            sections(&[slide(None), slide(Some("A")), slide(None), slide(Some("A")), slide(Some("B"))]),
+            vec![
+                (DEFAULT_SECTION.to_string(), vec![1]),
+                ("A".to_string(), vec![2, 3]),
+                ("A".to_string(), vec![4]),
+                ("B".to_string(), vec![5]),
+            ]
+        );
+    }
+}
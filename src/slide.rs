@@ -0,0 +1,70 @@
+//! Conteúdo de um slide, comum a todos os backends de saída
+
+use crate::editable::SlideObject;
+use crate::links::SlideLink;
+use std::path::{Path, PathBuf};
+
+/// Um slide: a imagem da página, camadas de build, notas do apresentador e navegação
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Slide {
+    /// Imagem que ocupa o slide inteiro
+    pub image: PathBuf,
+    /// Versão vetorial (SVG) da imagem, usada no lugar dela onde o formato aceita
+    pub vector: Option<PathBuf>,
+    /// Camadas do tamanho do slide (PNG com transparência), exibidas uma a uma por clique
+    pub builds: Vec<PathBuf>,
+    /// Notas do apresentador (vazio = sem notas)
+    pub notes: String,
+    /// Nome do slide, do sumário ou do rótulo da página (vazio = sem nome)
+    pub title: String,
+    /// Seção do sumário que começa neste slide
+    pub section: Option<String>,
+    /// Áreas clicáveis sobre a imagem (links do PDF)
+    pub links: Vec<SlideLink>,
+    /// Textos e imagens editáveis sobre a imagem, na ordem de pintura (modo editável)
+    pub objects: Vec<SlideObject>,
+}
+
+impl Slide {
+    pub fn new(image: &Path) -> Self {
+        Self {
+            image: image.to_path_buf(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_vector(mut self, vector: &Path) -> Self {
+        self.vector = Some(vector.to_path_buf());
+        self
+    }
+
+    pub fn with_notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = notes.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_section(mut self, section: impl Into<String>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    pub fn with_links(mut self, links: Vec<SlideLink>) -> Self {
+        self.links = links;
+        self
+    }
+
+    pub fn with_objects(mut self, objects: Vec<SlideObject>) -> Self {
+        self.objects = objects;
+        self
+    }
+
+    pub fn with_builds(mut self, builds: Vec<PathBuf>) -> Self {
+        self.builds = builds;
+        self
+    }
+}
@@ -0,0 +1,311 @@
+//! Corte das margens das páginas
+//! Antes da renderização, cada página ganha uma região (o conteúdo com uma folga ou uma
+//! das caixas do PDF) que passa a ser a área visível: imagens, links, objetos e SVGs
+//! saem todos recortados nela.
+
+use crate::conversion::CancellationToken;
+use crate::error::Pdf2KeyError;
+use crate::links::PixelRect;
+use crate::pdf_processor::PdfDocumentHandle;
+use image::RgbaImage;
+use std::collections::HashMap;
+
+/// Folga padrão em volta do conteúdo, em pontos
+pub const DEFAULT_PADDING: f32 = 12.0;
+
+/// Diferença máxima (por canal) da cor do fundo para um pixel contar como margem
+///
+/// Tolera o ruído de digitalizações e o antisserrilhado sem cortar texto claro.
+const BACKGROUND_TOLERANCE: u8 = 24;
+
+/// Caixa do PDF usada como área da página
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageBox {
+    /// CropBox: a área que os leitores de PDF mostram
+    Crop,
+    /// TrimBox: a página depois do corte da gráfica
+    Trim,
+    /// ArtBox: a área com o conteúdo que importa
+    Art,
+}
+
+/// Como achar a área útil de cada página
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrimSource {
+    /// Pixels diferentes da cor do fundo numa renderização reduzida (padrão)
+    #[default]
+    Pixels,
+    /// União das caixas dos objetos da página (textos, imagens, desenhos); retângulos
+    /// brancos de fundo contam como conteúdo
+    Objects,
+    /// Uma caixa definida no PDF; páginas sem ela ficam inteiras
+    Box(PageBox),
+}
+
+impl TrimSource {
+    /// Interpreta `pixels`, `objects`, `cropbox`, `trimbox` ou `artbox`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "pixels" => Some(TrimSource::Pixels),
+            "objects" => Some(TrimSource::Objects),
+            "cropbox" => Some(TrimSource::Box(PageBox::Crop)),
+            "trimbox" => Some(TrimSource::Box(PageBox::Trim)),
+            "artbox" => Some(TrimSource::Box(PageBox::Art)),
+            _ => None,
+        }
+    }
+}
+
+/// Corte das margens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trim {
+    pub source: TrimSource,
+    /// Folga em pontos em volta da área encontrada (padrão: `DEFAULT_PADDING`, ou zero com caixas do PDF)
+    pub padding: f32,
+    /// Mesmo corte em todas as páginas (a união das áreas), para o conteúdo não pular entre slides
+    pub uniform: bool,
+}
+
+impl Default for Trim {
+    fn default() -> Self {
+        Self::new(TrimSource::default())
+    }
+}
+
+impl Trim {
+    pub fn new(source: TrimSource) -> Self {
+        let padding = match source {
+            TrimSource::Box(_) => 0.0,
+            _ => DEFAULT_PADDING,
+        };
+        Self { source, padding, uniform: false }
+    }
+
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding.max(0.0);
+        self
+    }
+
+    pub fn with_uniform(mut self, uniform: bool) -> Self {
+        self.uniform = uniform;
+        self
+    }
+}
+
+/// Retângulo da página em pontos, com origem no canto superior esquerdo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PageRegion {
+    /// Menor região que contém as duas
+    pub fn union(&self, other: &PageRegion) -> PageRegion {
+        let (left, top) = (self.x.min(other.x), self.y.min(other.y));
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        PageRegion { x: left, y: top, width: right - left, height: bottom - top }
+    }
+
+    /// Parte da região dentro de `bounds`; `None` se não houver
+    pub fn intersect(&self, bounds: &PageRegion) -> Option<PageRegion> {
+        let (left, top) = (self.x.max(bounds.x), self.y.max(bounds.y));
+        let right = (self.x + self.width).min(bounds.x + bounds.width);
+        let bottom = (self.y + self.height).min(bounds.y + bounds.height);
+        (right > left && bottom > top).then_some(PageRegion { x: left, y: top, width: right - left, height: bottom - top })
+    }
+
+    /// A região com `padding` pontos a mais de cada lado
+    pub fn padded(&self, padding: f32) -> PageRegion {
+        PageRegion {
+            x: self.x - padding,
+            y: self.y - padding,
+            width: self.width + 2.0 * padding,
+            height: self.height + 2.0 * padding,
+        }
+    }
+}
+
+/// Região de cada página (índice 0-based) de `indices` depois do corte
+///
+/// Páginas em branco (ou sem a caixa pedida) ficam inteiras; com `trim.uniform`, todas
+/// recebem a união das regiões, limitada ao tamanho de cada uma.
+pub(crate) fn page_regions(
+    document: &PdfDocumentHandle,
+    indices: &[usize],
+    trim: &Trim,
+    cancellation: &CancellationToken,
+) -> Result<HashMap<usize, PageRegion>, Pdf2KeyError> {
+    let mut pages = Vec::with_capacity(indices.len());
+    for &index in indices {
+        cancellation.check()?;
+        pages.push((index, document.slide_region(index)?, document.content_bounds(index, trim.source)?));
+    }
+    Ok(trimmed_regions(&pages, trim))
+}
+
+/// Regiões depois do corte de cada página `(índice, área da página, conteúdo encontrado)`
+fn trimmed_regions(pages: &[(usize, PageRegion, Option<PageRegion>)], trim: &Trim) -> HashMap<usize, PageRegion> {
+    let mut regions: HashMap<usize, PageRegion> = pages
+        .iter()
+        .filter_map(|(index, page, content)| Some((*index, content.as_ref()?.padded(trim.padding).intersect(page)?)))
+        .collect();
+
+    if trim.uniform {
+        if let Some(all) = regions.values().copied().reduce(|a, b| a.union(&b)) {
+            regions = pages
+                .iter()
+                .filter_map(|(index, page, _)| all.intersect(page).map(|region| (*index, region)))
+                .collect();
+        }
+    }
+
+    let area = |region: &PageRegion| region.width * region.height;
+    let (kept, total) = pages.iter().fold((0.0, 0.0), |(kept, total), (index, page, _)| {
+        (kept + regions.get(index).map_or(area(page), area), total + area(page))
+    });
+    if total > 0.0 {
+        log::info!(
+            "{} de {} páginas recortadas ({:.0}% da área mantida)",
+            regions.len(),
+            pages.len(),
+            kept / total * 100.0
+        );
+    }
+    regions
+}
+
+/// Pixels da imagem diferentes da cor do fundo (a do canto superior esquerdo)
+///
+/// `None` se a imagem for toda da cor do fundo.
+pub(crate) fn pixel_bounds(image: &RgbaImage) -> Option<PixelRect> {
+    let ink = InkMap::new(image);
+    ink.bounds(&PixelRect { x: 0, y: 0, width: ink.width, height: ink.height })
+}
+
+/// Pixels com tinta (diferentes da cor do fundo, a do canto superior esquerdo)
+pub(crate) struct InkMap {
+    pub width: u32,
+    pub height: u32,
+    ink: Vec<bool>,
+}
+
+impl InkMap {
+    pub(crate) fn new(image: &RgbaImage) -> Self {
+        let background = image.get_pixel_checked(0, 0).copied().unwrap_or(image::Rgba([255, 255, 255, 255]));
+        let ink = image
+            .pixels()
+            .map(|pixel| pixel.0.iter().zip(background.0).any(|(&a, b)| a.abs_diff(b) > BACKGROUND_TOLERANCE))
+            .collect();
+        Self { width: image.width(), height: image.height(), ink }
+    }
+
+    pub(crate) fn at(&self, x: u32, y: u32) -> bool {
+        self.ink[(y * self.width + x) as usize]
+    }
+
+    /// Menor retângulo com toda a tinta dentro de `rect`; `None` se não houver
+    pub(crate) fn bounds(&self, rect: &PixelRect) -> Option<PixelRect> {
+        let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                if self.at(x, y) {
+                    left = left.min(x);
+                    top = top.min(y);
+                    right = right.max(x + 1);
+                    bottom = bottom.max(y + 1);
+                }
+            }
+        }
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some(PixelRect { x: left, y: top, width: right - left, height: bottom - top })
+    }
+
+    /// Pixels com tinta em cada coluna de `rect`
+    pub(crate) fn columns(&self, rect: &PixelRect) -> Vec<u32> {
+        (rect.x..rect.x + rect.width)
+            .map(|x| (rect.y..rect.y + rect.height).filter(|&y| self.at(x, y)).count() as u32)
+            .collect()
+    }
+
+    /// Pixels com tinta em cada linha de `rect`
+    pub(crate) fn rows(&self, rect: &PixelRect) -> Vec<u32> {
+        (rect.y..rect.y + rect.height)
+            .map(|y| (rect.x..rect.x + rect.width).filter(|&x| self.at(x, y)).count() as u32)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn region(x: f32, y: f32, width: f32, height: f32) -> PageRegion {
+        PageRegion { x, y, width, height }
+    }
+
+    /// Página branca de 100x80 com um bloco preto em x 60..90, y 10..30 e um cinza claro
+    /// (antisserrilhado) fora dele
+    fn off_centre() -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(100, 80, WHITE);
+        for y in 10..30 {
+            for x in 60..90 {
+                image.put_pixel(x, y, BLACK);
+            }
+        }
+        image.put_pixel(5, 70, Rgba([240, 240, 240, 255]));
+        image
+    }
+
+    #[test]
+    fn pixel_bounds_of_pages() {
+        assert_eq!(pixel_bounds(&RgbaImage::from_pixel(100, 80, WHITE)), None);
+        // Xadrez até as bordas: a página inteira
+        let full = RgbaImage::from_fn(100, 80, |x, y| if (x + y) % 2 == 0 { WHITE } else { BLACK });
+        assert_eq!(pixel_bounds(&full), Some(PixelRect { x: 0, y: 0, width: 100, height: 80 }));
+        assert_eq!(pixel_bounds(&off_centre()), Some(PixelRect { x: 60, y: 10, width: 30, height: 20 }));
+    }
+
+    #[test]
+    fn ink_map_counts() {
+        let ink = InkMap::new(&off_centre());
+        assert!(ink.at(60, 10) && !ink.at(59, 10) && !ink.at(5, 70));
+        let rect = PixelRect { x: 58, y: 8, width: 4, height: 3 };
+        assert_eq!(ink.columns(&rect), [0, 0, 1, 1]);
+        assert_eq!(ink.rows(&rect), [0, 0, 2]);
+        assert_eq!(ink.bounds(&PixelRect { x: 0, y: 40, width: 100, height: 40 }), None);
+        assert_eq!(ink.bounds(&rect), Some(PixelRect { x: 60, y: 10, width: 2, height: 1 }));
+    }
+
+    #[test]
+    fn regions_of_blank_full_and_off_centre_pages() {
+        let page = region(0.0, 0.0, 400.0, 300.0);
+        let pages = [
+            (0, page, None),
+            (1, page, Some(page)),
+            (2, page, Some(region(250.0, 20.0, 100.0, 50.0))),
+        ];
+        let trim = Trim::default().with_padding(10.0);
+        let regions = trimmed_regions(&pages, &trim);
+        // Em branco fica inteira (sem região); a folga não passa da página
+        assert_eq!(regions.get(&0), None);
+        assert_eq!(regions[&1], page);
+        assert_eq!(regions[&2], region(240.0, 10.0, 120.0, 70.0));
+
+        let regions = trimmed_regions(&pages, &trim.with_uniform(true));
+        assert_eq!(regions.len(), 3);
+        assert!(regions.values().all(|r| *r == page));
+        let regions = trimmed_regions(&[pages[0], pages[2], (3, page, Some(region(40.0, 200.0, 20.0, 20.0)))], &trim.with_uniform(true));
+        assert_eq!(regions[&0], region(30.0, 10.0, 330.0, 220.0));
+        assert_eq!(regions[&3], regions[&2]);
+    }
+}
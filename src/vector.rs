@@ -0,0 +1,311 @@
+//! Slides vetoriais: caminhos, textos e imagens da página convertidos em SVG
+//! A imagem renderizada continua sendo gerada: é a alternativa que os aplicativos usam
+//! onde o SVG não é aceito e substitui o SVG nas páginas com construções não suportadas.
+
+use crate::xml;
+use std::collections::HashMap;
+
+/// Resultado da conversão vetorial de uma página
+pub enum VectorPage {
+    /// Documento SVG completo, com as dimensões da imagem de `render_page`
+    Svg(String),
+    /// A página usa algo que o SVG gerado não reproduz (ex: "sombreamento"); fica a imagem
+    Unsupported(&'static str),
+}
+
+/// Matriz de transformação `[a b c d e f]`, como no PDF
+pub(crate) type Matrix = [f32; 6];
+
+/// Trecho de um caminho do PDF; curvas de Bézier chegam como três pontos seguidos
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Segment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    BezierTo(f32, f32),
+    Close,
+}
+
+/// Preenchimento e contorno de um caminho ou texto
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Paint {
+    /// Cor RGBA do preenchimento (`None` = sem preenchimento)
+    pub fill: Option<[u8; 4]>,
+    pub even_odd: bool,
+    /// Cor RGBA e largura do contorno (`None` = sem contorno)
+    pub stroke: Option<([u8; 4], f32)>,
+    /// Valores de `stroke-linejoin` e `stroke-linecap`
+    pub join: &'static str,
+    pub cap: &'static str,
+    pub dash: Vec<f32>,
+    pub dash_phase: f32,
+}
+
+/// Estilo de um trecho de texto
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextStyle {
+    pub font: String,
+    pub size: f32,
+    pub bold: bool,
+    pub italic: bool,
+    /// Largura do trecho no espaço do texto, para ajustar o espaçamento à fonte substituta
+    pub length: Option<f32>,
+}
+
+/// Monta o SVG de uma página, em coordenadas do PDF dentro de um grupo que as leva
+/// para os pixels da imagem renderizada
+pub(crate) struct SvgBuilder {
+    width: u32,
+    height: u32,
+    page_transform: Matrix,
+    defs: String,
+    body: String,
+    /// Recortes já definidos, pelo `d` do caminho
+    clips: HashMap<String, usize>,
+}
+
+impl SvgBuilder {
+    pub(crate) fn new(width: u32, height: u32, page_transform: Matrix) -> Self {
+        Self { width, height, page_transform, defs: String::new(), body: String::new(), clips: HashMap::new() }
+    }
+
+    /// Abre um grupo com `transform` e recortado por todos os caminhos de `clips`
+    ///
+    /// Retorna quantos `</g>` fecham o grupo (passar para [`SvgBuilder::end_group`]).
+    pub(crate) fn begin_group(&mut self, transform: Option<Matrix>, clips: &[String]) -> usize {
+        // Recortes múltiplos se intersectam: um grupo por recorte
+        for d in clips {
+            let count = self.clips.len();
+            let id = *self.clips.entry(d.clone()).or_insert_with(|| {
+                self.defs.push_str(&format!(
+                    r#"<clipPath id="c{}" clipPathUnits="userSpaceOnUse"><path d="{}"/></clipPath>"#,
+                    count, d
+                ));
+                count
+            });
+            self.body.push_str(&format!(r#"<g clip-path="url(#c{})">"#, id));
+        }
+        match transform {
+            Some(m) => {
+                self.body.push_str(&format!(r#"<g transform="{}">"#, matrix(&m)));
+                clips.len() + 1
+            }
+            None => clips.len(),
+        }
+    }
+
+    pub(crate) fn end_group(&mut self, depth: usize) {
+        self.body.push_str(&"</g>".repeat(depth));
+    }
+
+    pub(crate) fn path(&mut self, d: &str, transform: &Matrix, paint: &Paint) {
+        self.body.push_str(&format!(r#"<path d="{}" transform="{}"{}/>"#, d, matrix(transform), paint_attributes(paint)));
+    }
+
+    /// Texto na origem de `transform`; o espelhamento desfaz a inversão do eixo y do PDF
+    pub(crate) fn text(&mut self, text: &str, transform: &Matrix, style: &TextStyle, paint: &Paint) {
+        let length = match style.length {
+            Some(length) if length > 0.0 => format!(r#" textLength="{}" lengthAdjust="spacingAndGlyphs""#, number(length)),
+            _ => String::new(),
+        };
+        self.body.push_str(&format!(
+            r#"<text transform="{} scale(1 -1)" font-family="{}" font-size="{}"{}{}{}{} xml:space="preserve">{}</text>"#,
+            matrix(transform),
+            xml::escape(&style.font),
+            number(style.size),
+            if style.bold { r#" font-weight="bold""# } else { "" },
+            if style.italic { r#" font-style="italic""# } else { "" },
+            length,
+            paint_attributes(paint),
+            xml::escape(text)
+        ));
+    }
+
+    /// Imagem ocupando o quadrado unitário de `transform` (como no PDF)
+    pub(crate) fn image(&mut self, transform: &Matrix, mime: &str, bytes: &[u8]) {
+        // O quadrado unitário do PDF cresce para cima; o da imagem SVG, para baixo
+        self.body.push_str(&format!(
+            r#"<image transform="{} matrix(1 0 0 -1 0 1)" width="1" height="1" preserveAspectRatio="none" href="data:{};base64,{}"/>"#,
+            matrix(transform),
+            mime,
+            base64(bytes)
+        ));
+    }
+
+    /// Documento SVG com fundo branco, como a página renderizada
+    pub(crate) fn finish(self) -> String {
+        format!(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><defs>{defs}</defs><rect width="{w}" height="{h}" fill="#ffffff"/><g transform="{page}">{body}</g></svg>"##,
+            w = self.width,
+            h = self.height,
+            defs = self.defs,
+            page = matrix(&self.page_transform),
+            body = self.body
+        )
+    }
+}
+
+/// Atributo `d` de um caminho
+///
+/// Retorna `None` se as curvas estiverem incompletas (menos de três pontos de Bézier).
+pub(crate) fn path_data(segments: impl IntoIterator<Item = Segment>) -> Option<String> {
+    let mut d = String::new();
+    let mut bezier = Vec::with_capacity(3);
+    for segment in segments {
+        match segment {
+            Segment::MoveTo(x, y) => d.push_str(&format!("M{} {}", number(x), number(y))),
+            Segment::LineTo(x, y) => d.push_str(&format!("L{} {}", number(x), number(y))),
+            Segment::BezierTo(x, y) => {
+                bezier.push((x, y));
+                if bezier.len() == 3 {
+                    let points: Vec<String> = bezier.drain(..).map(|(x, y)| format!("{} {}", number(x), number(y))).collect();
+                    d.push_str(&format!("C{}", points.join(" ")));
+                }
+            }
+            Segment::Close => d.push('Z'),
+        }
+        if !bezier.is_empty() && !matches!(segment, Segment::BezierTo(..)) {
+            return None;
+        }
+    }
+    bezier.is_empty().then_some(d)
+}
+
+/// Produto de matrizes: aplica `inner` e depois `outer`
+pub(crate) fn multiply(inner: &Matrix, outer: &Matrix) -> Matrix {
+    let [a, b, c, d, e, f] = *inner;
+    let [a2, b2, c2, d2, e2, f2] = *outer;
+    [
+        a * a2 + b * c2,
+        a * b2 + b * d2,
+        c * a2 + d * c2,
+        c * b2 + d * d2,
+        e * a2 + f * c2 + e2,
+        e * b2 + f * d2 + f2,
+    ]
+}
+
+fn paint_attributes(paint: &Paint) -> String {
+    let mut attributes = match paint.fill {
+        Some(color) => {
+            let mut fill = format!(r#" fill="{}""#, hex(color));
+            if color[3] < u8::MAX {
+                fill.push_str(&format!(r#" fill-opacity="{}""#, number(color[3] as f32 / 255.0)));
+            }
+            if paint.even_odd {
+                fill.push_str(r#" fill-rule="evenodd""#);
+            }
+            fill
+        }
+        None => r#" fill="none""#.to_string(),
+    };
+    if let Some((color, width)) = paint.stroke {
+        attributes.push_str(&format!(
+            r#" stroke="{}" stroke-width="{}" stroke-linejoin="{}" stroke-linecap="{}""#,
+            hex(color),
+            // Largura zero no PDF é a linha mais fina do dispositivo
+            number(width.max(0.01)),
+            paint.join,
+            paint.cap
+        ));
+        if color[3] < u8::MAX {
+            attributes.push_str(&format!(r#" stroke-opacity="{}""#, number(color[3] as f32 / 255.0)));
+        }
+        if !paint.dash.is_empty() {
+            let dash: Vec<String> = paint.dash.iter().map(|&v| number(v)).collect();
+            attributes.push_str(&format!(r#" stroke-dasharray="{}" stroke-dashoffset="{}""#, dash.join(" "), number(paint.dash_phase)));
+        }
+    }
+    attributes
+}
+
+fn matrix(m: &Matrix) -> String {
+    let values: Vec<String> = m.iter().map(|&v| number(v)).collect();
+    format!("matrix({})", values.join(" "))
+}
+
+fn hex(color: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Número com até 3 casas decimais, sem zeros à direita
+fn number(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+/// Base64 padrão (RFC 4648), para as imagens embutidas no SVG
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_from_segments() {
+        use Segment::*;
+        assert_eq!(
+            path_data([MoveTo(0.0, 0.0), LineTo(10.5, 0.0), BezierTo(12.0, 1.0), BezierTo(12.0, 3.0), BezierTo(10.0, 4.0), Close]),
+            Some("M0 0L10.5 0C12 1 12 3 10 4Z".to_string())
+        );
+        assert_eq!(path_data([]), Some(String::new()));
+        // Curva com só dois pontos antes de outro trecho, ou no fim
+        assert_eq!(path_data([MoveTo(0.0, 0.0), BezierTo(1.0, 1.0), BezierTo(2.0, 2.0), LineTo(3.0, 3.0)]), None);
+        assert_eq!(path_data([MoveTo(0.0, 0.0), BezierTo(1.0, 1.0)]), None);
+    }
+
+    #[test]
+    fn matrix_product_applies_inner_first() {
+        let identity = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let scale = [2.0, 0.0, 0.0, 3.0, 0.0, 0.0];
+        let translate = [1.0, 0.0, 0.0, 1.0, 10.0, 20.0];
+        assert_eq!(multiply(&scale, &identity), scale);
+        // Escala e depois translada: a translação não é escalada
+        assert_eq!(multiply(&scale, &translate), [2.0, 0.0, 0.0, 3.0, 10.0, 20.0]);
+        assert_eq!(multiply(&translate, &scale), [2.0, 0.0, 0.0, 3.0, 20.0, 60.0]);
+        // Rotação de 90° duas vezes: meia volta
+        let quarter = [0.0, 1.0, -1.0, 0.0, 0.0, 0.0];
+        assert_eq!(multiply(&quarter, &quarter), [-1.0, 0.0, 0.0, -1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn numbers_are_short() {
+        assert_eq!(number(0.0), "0");
+        assert_eq!(number(100.0), "100");
+        assert_eq!(number(1.5), "1.5");
+        assert_eq!(number(2.0 / 3.0), "0.667");
+        assert_eq!(number(-0.0001), "0");
+        assert_eq!(number(-12.25), "-12.25");
+    }
+
+    #[test]
+    fn base64_matches_rfc_4648() {
+        for (input, expected) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64(input.as_bytes()), expected);
+        }
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    }
+}
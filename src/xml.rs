@@ -0,0 +1,21 @@
+//! Utilitários para gerar XML (PPTX e ODP)
+
+/// Escapa texto para uso em conteúdo ou atributos XML
+///
+/// Remove caracteres de controle que o XML 1.0 não aceita (comuns em texto extraído de PDFs).
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' => escaped.push(c),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}